@@ -0,0 +1,102 @@
+//! Headless benchmarks for the `SpatialGrid` used by the `SpatialIndex` resource.
+//!
+//! run with: `cargo bench --bench spatial_index --features bench`
+#![feature(test)]
+extern crate test;
+
+use bevy::prelude::*;
+use test::{black_box, Bencher};
+
+#[allow(dead_code)]
+#[path = "../src/horde_survivors/spatial/grid.rs"]
+mod grid;
+use grid::*;
+
+const NUM_ENTITIES: u32 = 5_000;
+const ARENA_HALF_SIZE: f32 = 60.0;
+const CELL_SIZE: f32 = 2.0;
+
+/// small xorshift, so the layout is the same on every run (and needs no extra deps)
+fn scatter(n: u32) -> Vec<SpatialEntry> {
+    let mut state: u32 = 0x9E37_79B9;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        (state as f32 / u32::MAX as f32) * 2.0 - 1.0
+    };
+
+    (0..n).map(|i| SpatialEntry {
+        entity: Entity::from_raw(i),
+        position: Vec2::new(next(), next()) * ARENA_HALF_SIZE,
+        radius: 0.4,
+        layers: 1,
+    }).collect()
+}
+
+fn filled_grid(entries: &[SpatialEntry]) -> SpatialGrid {
+    let mut grid = SpatialGrid::new(CELL_SIZE);
+    entries.iter().for_each(|e| grid.insert(*e));
+    grid
+}
+
+#[bench]
+fn rebuild(b: &mut Bencher) {
+    let entries = scatter(NUM_ENTITIES);
+    let mut grid = filled_grid(&entries);
+    b.iter(|| {
+        grid.clear();
+        entries.iter().for_each(|e| grid.insert(*e));
+        black_box(grid.len())
+    });
+}
+
+#[bench]
+fn within_radius(b: &mut Bencher) {
+    let grid = filled_grid(&scatter(NUM_ENTITIES));
+    b.iter(|| black_box(grid.within_radius(Vec2::new(3.0, -7.0), 5.0, u32::MAX)));
+}
+
+#[bench]
+fn in_aabb(b: &mut Bencher) {
+    let grid = filled_grid(&scatter(NUM_ENTITIES));
+    b.iter(|| black_box(grid.in_aabb(Vec2::new(-10.0, -6.0), Vec2::new(10.0, 6.0), u32::MAX)));
+}
+
+#[bench]
+fn k_nearest_8(b: &mut Bencher) {
+    let grid = filled_grid(&scatter(NUM_ENTITIES));
+    b.iter(|| black_box(grid.k_nearest(Vec2::new(3.0, -7.0), 8, u32::MAX)));
+}
+
+#[bench]
+fn nearest(b: &mut Bencher) {
+    let grid = filled_grid(&scatter(NUM_ENTITIES));
+    b.iter(|| black_box(grid.nearest(Vec2::new(3.0, -7.0), u32::MAX)));
+}
+
+/// baseline: what `nearest` replaces, a linear scan over every entity.
+#[bench]
+fn nearest_linear_scan(b: &mut Bencher) {
+    let entries = scatter(NUM_ENTITIES);
+    let point = Vec2::new(3.0, -7.0);
+    b.iter(|| {
+        black_box(entries.iter()
+            .min_by(|a, b| a.position.distance_squared(point).total_cmp(&b.position.distance_squared(point)))
+            .copied())
+    });
+}
+
+/// every entity asks for its neighbours, as the collision broad phase does each frame.
+#[bench]
+fn all_neighbour_queries(b: &mut Bencher) {
+    let entries = scatter(NUM_ENTITIES);
+    let grid = filled_grid(&entries);
+    b.iter(|| {
+        let mut pairs = 0usize;
+        for e in entries.iter() {
+            grid.for_each_in_radius(e.position, e.radius + grid.max_radius(), u32::MAX, |_| pairs += 1);
+        }
+        black_box(pairs)
+    });
+}
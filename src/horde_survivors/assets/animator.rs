@@ -1,10 +1,12 @@
 use bevy::{
+    animation::RepeatAnimation,
+    ecs::system::SystemParam,
     prelude::*,  
     utils::Duration,
     utils::hashbrown::HashMap,
 };
 
-use crate::GameLoopSchedules;
+use crate::{GameLoopSchedules, GameState};
 use super::types::*;
 
 /// when an `Entity` with an `AssetKey` and has a child `AnimationPlayer` added,
@@ -18,6 +20,30 @@ pub struct AnimationPlayerMapping(pub HashMap<Entity, Entity>);
 #[derive(Resource, Default)]
 pub struct AnimationPlayerReverseMapping(pub HashMap<Entity, Entity>);
 
+/// Tracks every `AnimationPlayer`'s `Entity` that is currently playing a one-shot animation (and which one),
+/// so looping animations can wait for it to play out.
+#[derive(Resource, Default)]
+pub struct OneShotAnimations(pub HashMap<Entity, AnimationType>);
+
+/// Looks up how long a root `Entity`'s animations run for, from the clips themselves.
+/// Lets the simulation wait out an animation in fixed ticks, rather than on the `AnimationPlayer` which
+/// advances with the frame rate.
+#[derive(SystemParam)]
+pub struct AnimationLengths<'w> {
+    assets: Res<'w, MeshAssetMap>,
+    asset_map: Res<'w, EntityAssetMapping>,
+    clips: Res<'w, Assets<AnimationClip>>,
+}
+impl AnimationLengths<'_> {
+    /// seconds, `None` if the `Entity` has no such animation.
+    pub fn get(&self, entity: Entity, animation_type: AnimationType) -> Option<f32> {
+        let asset_key = self.asset_map.0.get(&entity)?;
+        let animations = self.assets.0.get(&asset_key.0)?.animations.as_ref()?;
+        let clip = self.clips.get(animations.0.get(&animation_type)?)?;
+        Some(clip.duration())
+    }
+}
+
 
 pub struct MeshAnimatorPlugin;
 impl Plugin for MeshAnimatorPlugin {
@@ -26,19 +52,35 @@ impl Plugin for MeshAnimatorPlugin {
             // Resources
             .insert_resource(AnimationPlayerMapping::default())
             .insert_resource(AnimationPlayerReverseMapping::default())
+            .insert_resource(OneShotAnimations::default())
 
             // Events
             .add_event::<TriggerAnimation>()
             
             // Systems
-            .add_systems(Update, 
+            .add_systems(FixedUpdate, 
                 associate_animation_players_to_root_entities
+                .run_if(in_state(GameState::Playing))
                 .in_set(GameLoopSchedules::PostSpawn))
 
-            .add_systems(Update, start_idle_animation
+            .add_systems(FixedUpdate, start_idle_animation
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates))
+            .add_systems(FixedUpdate, trigger_animation
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates))
+            .add_systems(FixedUpdate, finish_one_shot_animations
+                .after(trigger_animation)
+                .run_if(in_state(GameState::Playing))
                 .in_set(GameLoopSchedules::EntityUpdates))
-            .add_systems(Update, trigger_animation
+            .add_systems(FixedUpdate, apply_animation_speed
+                .after(start_idle_animation)
+                .after(trigger_animation)
+                .run_if(in_state(GameState::Playing))
                 .in_set(GameLoopSchedules::EntityUpdates))
+
+            // not limited to `Playing`, removals have to be read every frame or they're missed.
+            .add_systems(Update, forget_despawned_animation_players)
         ;
     }
 }
@@ -104,12 +146,10 @@ fn trigger_animation(
     assets: Res<MeshAssetMap>,
     asset_map: Res<EntityAssetMapping>,
     animator_map: Res<AnimationPlayerMapping>,
+    mut one_shots: ResMut<OneShotAnimations>,
     mut q_animators: Query<&mut AnimationPlayer>,
 ) {
     for event in events.read() {
-        let animator_entity = if let Some(entity) = animator_map.0.get(&event.0) { 
-            *entity 
-        } else { continue; };
         let asset_key = if let Some(key) = asset_map.0.get(&event.0) { 
             key 
         } else { continue; };
@@ -119,16 +159,196 @@ fn trigger_animation(
         let animations = if let Some(animations) = &entity_assets.animations { 
             animations
         } else { continue; };
+        let animator_entity = if let Some(entity) = animator_map.0.get(&event.0) { 
+            *entity 
+        } else { continue; };
 
+        // once dying, nothing else gets to interrupt it.
+        if one_shots.0.get(&animator_entity) == Some(&AnimationType::Die) { continue; }
+        // looping animations wait for a one-shot to play out, whoever wants them sends them every frame anyway.
+        if event.1.is_looping() && one_shots.0.contains_key(&animator_entity) { continue; }
 
         if let Ok(mut animator) = q_animators.get_mut(animator_entity) {
             animator.play_with_transition(
                 animations.0.get(&event.1).expect("all animation types must be set").clone_weak(),
                 Duration::from_millis(250),
-                )
-                .repeat();
+            );
+
+            if event.1.is_looping() {
+                animator.repeat();
+                one_shots.0.remove(&animator_entity);
+            } else {
+                animator.set_repeat(RepeatAnimation::Never);
+                one_shots.0.insert(animator_entity, event.1);
+            }
         } else {
             warn!("no AnimationPlayer for this entity!");
         }
     }
 }
+
+fn finish_one_shot_animations(
+    mut one_shots: ResMut<OneShotAnimations>,
+    q_animators: Query<&AnimationPlayer>,
+) {
+    one_shots.0.retain(|animator_entity, _| {
+        q_animators.get(*animator_entity).is_ok_and(|animator| !animator.is_finished())
+    });
+}
+
+/// starting an animation resets the `AnimationPlayer`'s speed, so this has to run after anything that might.
+fn apply_animation_speed(
+    animator_map: Res<AnimationPlayerMapping>,
+    q_roots: Query<(Entity, &AnimationSpeed)>,
+    mut q_animators: Query<&mut AnimationPlayer>,
+) {
+    for (root_entity, speed) in q_roots.iter() {
+        let animator_entity = if let Some(entity) = animator_map.0.get(&root_entity) {
+            *entity
+        } else { continue; };
+
+        if let Ok(mut animator) = q_animators.get_mut(animator_entity) {
+            if animator.speed() != speed.0 {
+                animator.set_speed(speed.0);
+            }
+        }
+    }
+}
+
+/// Keeps `AnimationPlayerMapping`, `AnimationPlayerReverseMapping` and `OneShotAnimations` in sync as
+/// entities are despawned. Either side of a mapping going away removes both directions.
+fn forget_despawned_animation_players(
+    mut removed_roots: RemovedComponents<AssetKey>,
+    mut removed_players: RemovedComponents<AnimationPlayer>,
+    mut mapping: ResMut<AnimationPlayerMapping>,
+    mut rev_mapping: ResMut<AnimationPlayerReverseMapping>,
+    mut one_shots: ResMut<OneShotAnimations>,
+) {
+    for root_entity in removed_roots.read() {
+        if let Some(animator_entity) = mapping.0.remove(&root_entity) {
+            rev_mapping.0.remove(&animator_entity);
+            one_shots.0.remove(&animator_entity);
+        }
+    }
+
+    for animator_entity in removed_players.read() {
+        one_shots.0.remove(&animator_entity);
+        if let Some(root_entity) = rev_mapping.0.remove(&animator_entity) {
+            // only if the root hasn't already been given a newer `AnimationPlayer`
+            if mapping.0.get(&root_entity) == Some(&animator_entity) {
+                mapping.0.remove(&root_entity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::mesh_spawner::spawn_mesh;
+
+    const ENTITIES: usize = 2_000;
+    const CYCLES: usize = 5;
+
+    fn app() -> App {
+        let animation_types = [
+            AnimationType::Idle, AnimationType::Walk, AnimationType::Run,
+            AnimationType::TakeHit, AnimationType::Attack, AnimationType::Die,
+        ];
+        // never loaded, only weak handles to them are ever handed out
+        let animations = Animations(animation_types.into_iter().map(|t| (t, Handle::default())).collect());
+
+        let mut app = App::new();
+        app
+            .insert_resource(MeshAssetMap(HashMap::from([(
+                "skeleton".to_string(),
+                MeshAssets { _gltf: Handle::default(), mesh: Handle::default(), animations: Some(animations) },
+            )])))
+            .insert_resource(EntityAssetMapping::default())
+            .insert_resource(AnimationPlayerMapping::default())
+            .insert_resource(AnimationPlayerReverseMapping::default())
+            .insert_resource(OneShotAnimations::default())
+            .add_event::<SpawnMesh>()
+            .add_event::<TriggerAnimation>()
+            .add_systems(Update, (
+                spawn_mesh,
+                associate_animation_players_to_root_entities,
+                trigger_animation,
+                finish_one_shot_animations,
+                forget_despawned_animation_players,
+            ).chain());
+        app
+    }
+
+    /// spawns `n` animated roots, the way anything with a mesh is.
+    fn spawn_animated(app: &mut App, n: usize) -> Vec<Entity> {
+        let key = AssetKey("skeleton".into());
+        let roots = (0..n).map(|_| {
+            let root = app.world.spawn((SpatialBundle::default(), key.clone())).id();
+            app.world.send_event(SpawnMesh(root, key.clone(), Transform::default()));
+            root
+        }).collect();
+        app.update();
+
+        // what the `SceneSpawner` would leave under each new mesh, an `AnimationPlayer` somewhere down its hierarchy
+        let scenes: Vec<_> = app.world.query_filtered::<Entity, (With<Handle<Scene>>, Without<Children>)>()
+            .iter(&app.world)
+            .collect();
+        for scene in scenes {
+            app.world.entity_mut(scene).with_children(|parent| {
+                parent.spawn(SpatialBundle::default()).with_children(|parent| {
+                    parent.spawn(AnimationPlayer::default());
+                });
+            });
+        }
+        app.update();
+        roots
+    }
+
+    fn mapped(world: &World) -> (usize, usize, usize) {
+        (
+            world.resource::<AnimationPlayerMapping>().0.len(),
+            world.resource::<AnimationPlayerReverseMapping>().0.len(),
+            world.resource::<OneShotAnimations>().0.len(),
+        )
+    }
+
+    #[test]
+    fn despawned_entities_are_forgotten() {
+        let mut app = app();
+
+        // one that sticks around, so the mappings aren't just emptied out
+        spawn_animated(&mut app, 1);
+        let start = mapped(&app.world);
+        assert_eq!(start, (1, 1, 0));
+        let entities = app.world.entities().len();
+
+        for _ in 0..CYCLES {
+            let roots = spawn_animated(&mut app, ENTITIES);
+            assert_eq!(mapped(&app.world), (1 + ENTITIES, 1 + ENTITIES, 0));
+
+            // half of them part way through dying, as they would be
+            for root in roots.iter().step_by(2) {
+                app.world.send_event(TriggerAnimation(*root, AnimationType::Die));
+            }
+            app.update();
+            assert_eq!(mapped(&app.world), (1 + ENTITIES, 1 + ENTITIES, ENTITIES / 2));
+
+            // some lose their mesh first, the root is still around (and mapped to nothing) for a frame
+            let (first, rest) = roots.split_at(ENTITIES / 4);
+            for root in first {
+                let mesh = app.world.get::<Children>(*root).expect("spawned with a mesh")[0];
+                app.world.entity_mut(mesh).despawn_recursive();
+            }
+            app.update();
+            assert_eq!(mapped(&app.world).0, 1 + rest.len());
+
+            for root in roots {
+                app.world.entity_mut(root).despawn_recursive();
+            }
+            app.update();
+            assert_eq!(mapped(&app.world), start);
+            assert_eq!(app.world.entities().len(), entities);
+        }
+    }
+}
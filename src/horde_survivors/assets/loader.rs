@@ -56,12 +56,14 @@ fn load_meshes(
     player_animations.insert(AnimationType::Walk, asset_server.load("Anne.glb#Animation11"));   // walk 12
     player_animations.insert(AnimationType::Run, asset_server.load("Anne.glb#Animation9"));     // run 10
     player_animations.insert(AnimationType::TakeHit, asset_server.load("Anne.glb#Animation2")); // take hit 3
+    player_animations.insert(AnimationType::Attack, asset_server.load("Anne.glb#Animation10")); // sword 11
     player_animations.insert(AnimationType::Die, asset_server.load("Anne.glb#Animation0"));     // die 1
     player_animations.iter().for_each(|(_, a)| { 
         loading_assets.0.insert(a.clone_weak().untyped().id(), LoadState::NotLoaded); 
     });
 
     let player_assets = MeshAssets{
+        _gltf: asset_server.load("Anne.glb"),
         mesh: asset_server.load("Anne.glb#Scene0"),
         animations: Some(Animations(player_animations)),
     };
@@ -74,11 +76,13 @@ fn load_meshes(
     enemy_animations.insert(AnimationType::Walk, asset_server.load("Skeleton.glb#Animation12"));   // walk 13
     enemy_animations.insert(AnimationType::Run, asset_server.load("Skeleton.glb#Animation10"));    // run 11
     enemy_animations.insert(AnimationType::TakeHit, asset_server.load("Skeleton.glb#Animation2")); // take hit 3
+    enemy_animations.insert(AnimationType::Attack, asset_server.load("Skeleton.glb#Animation11")); // sword 12
     enemy_animations.insert(AnimationType::Die, asset_server.load("Skeleton.glb#Animation0"));     // die 1
     enemy_animations.iter().for_each(|(_, a)| { 
         loading_assets.0.insert(a.clone_weak().untyped().id(), LoadState::NotLoaded); 
     });
     let enemy_assets = MeshAssets{
+        _gltf: asset_server.load("Skeleton.glb"),
         mesh: asset_server.load("Skeleton.glb#Scene0"),
         animations: Some(Animations(enemy_animations)),
     };
@@ -86,12 +90,14 @@ fn load_meshes(
     
     //  Throwing Dagger
     let projectile_assets = MeshAssets{
+        _gltf: asset_server.load("Dagger.glb"),
         mesh:asset_server.load("Dagger.glb#Scene0"),
         animations: None,
     };
     loading_assets.0.insert(projectile_assets.mesh.clone_weak().untyped().id(), LoadState::NotLoaded);
 
     let destructible_assets = MeshAssets{
+        _gltf: asset_server.load("Torch.glb"),
         mesh:asset_server.load("Torch.glb#Scene0"),
         animations: None,
     };
@@ -143,7 +149,7 @@ fn update_loading_progress(
         info!("{:?} assets loaded", total_loading);
 
         // set next state
-        next_state.set(GameState::Initialize);
+        next_state.set(GameState::MainMenu);
     }
 }
 
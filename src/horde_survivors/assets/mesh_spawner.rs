@@ -1,4 +1,4 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::hashbrown::HashMap};
 
 use crate::{GameLoopSchedules, GameState};
 use super::types::*;
@@ -14,15 +14,19 @@ impl Plugin for MeshSpawnerPlugin {
             .add_event::<SpawnMesh>()
 
             //systems
-            .add_systems(Update, 
+            .add_systems(FixedUpdate, 
                 spawn_mesh
                 .run_if(in_state(GameState::Playing))
-                .in_set(GameLoopSchedules::Spawn)
-        );
+                .in_set(GameLoopSchedules::PostSpawn)
+            )
+            // scenes finish spawning their meshes whenever they're ready, so this just watches for new materials.
+            .add_systems(Update, tint_meshes)
+            // not limited to `Playing`, removals have to be read every frame or they're missed.
+            .add_systems(Update, forget_despawned_entities);
     }
 }
 
-fn spawn_mesh(
+pub(super) fn spawn_mesh(
     mut commands: Commands,
     mut events: EventReader<SpawnMesh>,
     assets: Res<MeshAssetMap>,
@@ -31,6 +35,11 @@ fn spawn_mesh(
     for event in events.read() {
         let (entity, asset_key, initial_transform) = (event.0, event.1.clone(), event.2);
 
+        // the entity can be gone before its mesh is spawned (ie. a projectile that hit something on its first frame)
+        if commands.get_entity(entity).is_none() {
+            continue;
+        }
+
         let mesh_assets = if let Some(res) = assets.0.get(&asset_key.0) { 
             info!("associating mesh asset for entity: {:?} =uses=> {:?}", entity, asset_key);
             asset_map.0.insert(entity, asset_key.clone());
@@ -48,3 +57,115 @@ fn spawn_mesh(
         commands.entity(entity).add_child(mesh_id);
     }
 }
+
+/// Swaps the materials of any newly spawned mesh under a `MeshTint` for tinted copies.
+/// The copies are shared between every mesh using the same material and tint.
+fn tint_meshes(
+    mut q_materials: Query<(Entity, &mut Handle<StandardMaterial>), Added<Handle<StandardMaterial>>>,
+    q_parents: Query<&Parent>,
+    q_tints: Query<&MeshTint>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut tinted: Local<HashMap<(AssetId<StandardMaterial>, [u8; 4]), Handle<StandardMaterial>>>,
+) {
+    for (entity, mut material) in q_materials.iter_mut() {
+        let tint = if let Some(tint) = q_parents.iter_ancestors(entity).find_map(|e| q_tints.get(e).ok()) {
+            tint.0
+        } else { continue; };
+        if tint == Color::WHITE { continue; }
+
+        let key = (material.id(), tint.as_rgba_u8());
+        if let Some(handle) = tinted.get(&key) {
+            *material = handle.clone();
+            continue;
+        }
+
+        let mut copy = if let Some(original) = materials.get(material.id()) {
+            original.clone()
+        } else { continue; };
+        copy.base_color *= Vec4::from(tint.as_rgba_f32());
+
+        let handle = materials.add(copy);
+        tinted.insert(key, handle.clone());
+        *material = handle;
+    }
+}
+
+/// `AssetKey` is only ever removed by despawning, so this keeps `EntityAssetMapping` from
+/// holding onto (and possibly handing out to a recycled `Entity`) stale asset keys.
+fn forget_despawned_entities(
+    mut removed: RemovedComponents<AssetKey>,
+    mut asset_map: ResMut<EntityAssetMapping>,
+) {
+    for entity in removed.read() {
+        asset_map.0.remove(&entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENTITIES: usize = 2_000;
+    const CYCLES: usize = 5;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app
+            // never loaded, `spawn_mesh` only hands out weak handles to them
+            .insert_resource(MeshAssetMap(HashMap::from([(
+                "skeleton".to_string(),
+                MeshAssets { _gltf: Handle::default(), mesh: Handle::default(), animations: None },
+            )])))
+            .insert_resource(EntityAssetMapping::default())
+            .add_event::<SpawnMesh>()
+            .add_systems(Update, (spawn_mesh, forget_despawned_entities));
+        app
+    }
+
+    /// spawns `n` entities the way anything with a mesh is, `spawn_mesh` picks them up on the next update.
+    fn spawn(world: &mut World, n: usize) -> Vec<Entity> {
+        let key = AssetKey("skeleton".into());
+        (0..n).map(|_| {
+            let entity = world.spawn((SpatialBundle::default(), key.clone())).id();
+            world.send_event(SpawnMesh(entity, key.clone(), Transform::default()));
+            entity
+        }).collect()
+    }
+
+    fn mapped(world: &World) -> usize {
+        let mapping = &world.resource::<EntityAssetMapping>().0;
+        // entities are recycled, nothing stale should be left for a new one to pick up
+        assert!(mapping.keys().all(|entity| world.get::<AssetKey>(*entity).is_some()));
+        mapping.len()
+    }
+
+    #[test]
+    fn despawned_entities_are_forgotten() {
+        let mut app = app();
+
+        // one that sticks around, so the mapping isn't just emptied out
+        spawn(&mut app.world, 1);
+        app.update();
+        assert_eq!(mapped(&app.world), 1);
+        let entities = app.world.entities().len();
+
+        for _ in 0..CYCLES {
+            let mut spawned = spawn(&mut app.world, ENTITIES);
+            // some are gone before their mesh is spawned, ie. a projectile that hit something straight away
+            for entity in spawned.drain(..ENTITIES / 10) {
+                app.world.despawn(entity);
+            }
+            app.update();
+            assert_eq!(mapped(&app.world), 1 + spawned.len());
+            assert!(spawned.iter().all(|entity| app.world.get::<Children>(*entity).is_some_and(|c| c.len() == 1)));
+
+            for entity in spawned {
+                app.world.entity_mut(entity).despawn_recursive();
+            }
+            app.update();
+            assert_eq!(mapped(&app.world), 1);
+            // the meshes went with them
+            assert_eq!(app.world.entities().len(), entities);
+        }
+    }
+}
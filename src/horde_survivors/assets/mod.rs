@@ -3,7 +3,9 @@ pub mod plugin;
 pub mod types;
 
 mod loader;
+pub use loader::LoadingAssets;
 mod animator;
+pub use animator::AnimationLengths;
 mod mesh_spawner;
 
 pub(super) use crate::horde_survivors::*;
@@ -1,4 +1,5 @@
 use bevy::{
+    gltf::Gltf,
     prelude::*, 
     utils::hashbrown::HashMap
 };
@@ -18,6 +19,21 @@ pub const ASSET_KEY_PROJECTILE: &str = "projectile";
 pub struct AssetKey(pub String);
 
 
+/// Multiplied into the color of every material in the `Entity`'s mesh, once it's spawned.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MeshTint(pub Color);
+
+
+/// Playback speed of the `Entity`'s animations (1 is normal speed), kept applied as animations change.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct AnimationSpeed(pub f32);
+impl Default for AnimationSpeed {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+
 #[derive(PartialEq, Eq, Hash, Debug, Default, Clone, Copy)]
 pub enum AnimationType {
     #[default]
@@ -25,8 +41,15 @@ pub enum AnimationType {
     Walk,
     Run,
     TakeHit,
+    Attack,
     Die,
 }
+impl AnimationType {
+    /// one-shot animations play once (instead of repeating), looping ones wait for them to finish.
+    pub fn is_looping(&self) -> bool {
+        !matches!(self, AnimationType::TakeHit | AnimationType::Attack | AnimationType::Die)
+    }
+}
 
 #[derive(Event, Debug)]
 pub struct LoadingUpdate(pub usize, pub usize);
@@ -45,6 +68,9 @@ pub struct SpawnMesh(pub Entity, pub AssetKey, pub Transform);
 // =================================
 
 pub(super) struct MeshAssets {
+    /// the file the mesh (and animations) come from, held so it isn't dropped
+    /// while its labeled parts are still loading (bevy 0.13.0 can panic inserting it then).
+    pub _gltf: Handle<Gltf>,
     pub mesh: Handle<Scene>,
     pub animations: Option<Animations>,
 }
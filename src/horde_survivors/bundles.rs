@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::{Acceleration, Velocity};
+use crate::{Acceleration, Collider, CollisionLayers, Velocity};
 
 
 #[derive(Bundle, Default)]
@@ -8,12 +8,13 @@ pub struct MovableObjectBundle {
     pub transform: SpatialBundle,
     pub velocity: Velocity,
     pub acceleration: Acceleration,
-
-    // TODO: collider / bounding box?
+    pub collider: Collider,
+    pub layers: CollisionLayers,
 }
 
 #[derive(Bundle, Default)]
 pub struct StaticObjectBundle {
     pub transform: SpatialBundle,
-    // TODO: collider / bounding box?
+    pub collider: Collider,
+    pub layers: CollisionLayers,
 }
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::{GameLoopSchedules, PlayerComponent};
+use crate::{GameState, PlayerComponent};
 
 const CAMERA_DISTANCE: f32 = 20.0;
 const CAMERA_FOLLOW_SPEED: f32 = 1.5;
@@ -34,9 +34,10 @@ pub struct CameraPlugin;
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, spawn_camera)
+            .add_systems(OnEnter(GameState::Initialize), reset_camera)
             .add_systems(Update, 
                 follow_player
-                .after_ignore_deferred(GameLoopSchedules::EntityUpdates)
+                .run_if(in_state(GameState::Playing))
             )
             ;
     }
@@ -60,6 +61,15 @@ fn spawn_camera(mut commands: Commands) {
     });
 }
 
+/// back over the middle of the map, where the next player spawns
+fn reset_camera(
+    mut camera: Query<&mut Transform, With<MainCamera>>,
+) {
+    for mut t in camera.iter_mut() {
+        *t = Transform::default();
+    }
+}
+
 fn follow_player(
     time: Res<Time>,
     player: Query<&Transform, (With<PlayerComponent>, Without<MainCamera>)>,
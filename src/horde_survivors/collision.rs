@@ -0,0 +1,250 @@
+use bevy::{
+    prelude::*,
+    utils::hashbrown::HashSet,
+};
+
+use crate::{reset_run_resource, GameLoopSchedules, GameState, SpatialIndex};
+
+// collision layers, used as bit flags for `CollisionLayers`
+pub const LAYER_PLAYER: u32      = 1 << 0;
+pub const LAYER_ENEMY: u32       = 1 << 1;
+pub const LAYER_PICKUP: u32      = 1 << 2;
+pub const LAYER_PROJECTILE: u32  = 1 << 3;
+pub const LAYER_DESTRUCTIBLE: u32 = 1 << 4;
+pub const LAYER_STATIC: u32      = 1 << 5;
+
+
+/// Shape of a `Collider`, measured on the XY (ground) plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColliderShape {
+    Circle { radius: f32 },
+    /// A capsule is a segment (along the entity's local Y axis) 'inflated' by `radius`.
+    /// `half_length` is the distance from the center to either end of that segment.
+    Capsule { half_length: f32, radius: f32 },
+}
+impl Default for ColliderShape {
+    fn default() -> Self {
+        ColliderShape::Circle { radius: 0.5 }
+    }
+}
+
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Collider(pub ColliderShape);
+impl Collider {
+    pub fn circle(radius: f32) -> Self {
+        Self(ColliderShape::Circle { radius })
+    }
+    pub fn capsule(half_length: f32, radius: f32) -> Self {
+        Self(ColliderShape::Capsule { half_length, radius })
+    }
+
+    /// the radius of a circle (centered on the entity) that fully contains this collider.
+    pub fn bounding_radius(&self) -> f32 {
+        match self.0 {
+            ColliderShape::Circle { radius } => radius,
+            ColliderShape::Capsule { half_length, radius } => half_length + radius,
+        }
+    }
+
+    /// every shape is treated as a (possibly zero length) segment plus a radius,
+    /// returns the segment's ends (on the XY plane, placed by `transform`) and that radius.
+    pub fn segment(&self, transform: &Transform) -> (Vec2, Vec2, f32) {
+        let center = transform.translation.truncate();
+        match self.0 {
+            ColliderShape::Circle { radius } => (center, center, radius),
+            ColliderShape::Capsule { half_length, radius } => {
+                let axis = (transform.rotation * Vec3::Y).truncate().normalize_or_zero() * half_length;
+                (center - axis, center + axis, radius)
+            },
+        }
+    }
+}
+
+/// Which layers an `Entity` is a member of, and which layers it wants to collide with.
+/// Two colliders interact if either one's `filters` contains any of the other's `memberships`.
+///
+/// The default is no layers at all, so a default `Collider` never produces events.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct CollisionLayers {
+    pub memberships: u32,
+    pub filters: u32,
+}
+impl CollisionLayers {
+    pub fn new(memberships: u32, filters: u32) -> Self {
+        Self { memberships, filters }
+    }
+
+    pub fn interacts_with(&self, other: &CollisionLayers) -> bool {
+        (self.filters & other.memberships) != 0 || (other.filters & self.memberships) != 0
+    }
+}
+
+/// Result of a narrow-phase check between two colliders.
+/// `normal` points from the first collider towards the second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    pub normal: Vec2,
+    pub depth: f32,
+}
+
+/// Sent the first frame two colliders overlap. Entities are ordered (lowest first).
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CollisionStarted(pub Entity, pub Entity);
+
+/// Sent the first frame two colliders stop overlapping (or one of them is gone). Entities are ordered (lowest first).
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CollisionEnded(pub Entity, pub Entity);
+
+/// All pairs of entities that were overlapping as of the last `CollisionDetection` run,
+/// a pair that's no longer in here has stopped overlapping (and had a `CollisionEnded` sent).
+#[derive(Resource, Debug, Default)]
+pub struct ActiveCollisions(pub HashSet<(Entity, Entity)>);
+
+
+pub struct CollisionPlugin;
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Resources
+            .insert_resource(ActiveCollisions::default())
+
+            // Events
+            .add_event::<CollisionStarted>()
+            .add_event::<CollisionEnded>()
+
+            // Systems
+            .add_systems(OnEnter(GameState::Initialize), reset_run_resource::<ActiveCollisions>)
+            .add_systems(FixedUpdate,
+                detect_collisions
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::CollisionDetection)
+            )
+        ;
+    }
+}
+
+pub fn detect_collisions(
+    index: Res<SpatialIndex>,
+    q_colliders: Query<(Entity, &Transform, &Collider, &CollisionLayers)>,
+    mut active: ResMut<ActiveCollisions>,
+    mut started: EventWriter<CollisionStarted>,
+    mut ended: EventWriter<CollisionEnded>,
+) {
+    let mut current = HashSet::with_capacity(active.0.len());
+    let mut checked = HashSet::new();
+    // in the order they were found, not the `HashSet`'s, so the hits land the same way on every replay
+    let mut new_pairs = Vec::new();
+
+    // broad phase: only entries close enough for their bounding circles to touch are checked.
+    let max_radius = index.0.max_radius();
+    for (a, a_t, a_col, a_layers) in q_colliders.iter() {
+        let reach = a_col.bounding_radius() + max_radius;
+        index.0.for_each_in_radius(a_t.translation.truncate(), reach, u32::MAX, |entry| {
+            if entry.entity == a { return; }
+            // each pair is only checked once, from whichever side is reached first
+            let pair = ordered_pair(a, entry.entity);
+            if !checked.insert(pair) { return; }
+            let Ok((_, b_t, b_col, b_layers)) = q_colliders.get(entry.entity) else { return; };
+
+            if !a_layers.interacts_with(b_layers) { return; }
+            if contact(a_t, a_col, b_t, b_col).is_none() { return; }
+
+            current.insert(pair);
+            if !active.0.contains(&pair) {
+                new_pairs.push(pair);
+            }
+        });
+    }
+
+    for pair in new_pairs {
+        started.send(CollisionStarted(pair.0, pair.1));
+    }
+
+    // same reason as `new_pairs`, these come out of a `HashSet` so they're sorted first
+    let mut ended_pairs: Vec<_> = active.0.difference(&current).copied().collect();
+    ended_pairs.sort_unstable();
+    for pair in ended_pairs {
+        ended.send(CollisionEnded(pair.0, pair.1));
+    }
+
+    active.0 = current;
+}
+
+/// Narrow-phase check between two colliders, only the XY components of each `Transform` are considered.
+pub fn contact(
+    a_t: &Transform, a_col: &Collider,
+    b_t: &Transform, b_col: &Collider,
+) -> Option<Contact> {
+    let (a0, a1, a_radius) = a_col.segment(a_t);
+    let (b0, b1, b_radius) = b_col.segment(b_t);
+
+    let (pa, pb) = closest_points_between_segments(a0, a1, b0, b1);
+    let delta = pb - pa;
+    let dist_sq = delta.length_squared();
+    let radii = a_radius + b_radius;
+    if dist_sq >= radii * radii { return None; }
+
+    let dist = dist_sq.sqrt();
+    let normal = if dist > f32::EPSILON {
+        delta / dist
+    } else {
+        // centers are on top of each other, just pick a direction
+        Vec2::X
+    };
+
+    Some(Contact { normal, depth: radii - dist })
+}
+
+fn closest_points_between_segments(p0: Vec2, p1: Vec2, q0: Vec2, q1: Vec2) -> (Vec2, Vec2) {
+    let d1 = p1 - p0;
+    let d2 = q1 - q0;
+    let r = p0 - q0;
+    let a = d1.length_squared();
+    let e = d2.length_squared();
+    let f = d2.dot(r);
+
+    // both segments degenerate into points
+    if a <= f32::EPSILON && e <= f32::EPSILON {
+        return (p0, q0);
+    }
+
+    let (s, t);
+    if a <= f32::EPSILON {
+        s = 0.0;
+        t = (f / e).clamp(0.0, 1.0);
+    } else {
+        let c = d1.dot(r);
+        if e <= f32::EPSILON {
+            t = 0.0;
+            s = (-c / a).clamp(0.0, 1.0);
+        } else {
+            let b = d1.dot(d2);
+            let denom = a * e - b * b;
+
+            let s_initial = if denom > f32::EPSILON {
+                ((b * f - c * e) / denom).clamp(0.0, 1.0)
+            } else {
+                // parallel segments, any point works
+                0.0
+            };
+
+            let t_initial = (b * s_initial + f) / e;
+            if t_initial < 0.0 {
+                t = 0.0;
+                s = (-c / a).clamp(0.0, 1.0);
+            } else if t_initial > 1.0 {
+                t = 1.0;
+                s = ((b - c) / a).clamp(0.0, 1.0);
+            } else {
+                t = t_initial;
+                s = s_initial;
+            }
+        }
+    }
+
+    (p0 + d1 * s, q0 + d2 * t)
+}
+
+fn ordered_pair(a: Entity, b: Entity) -> (Entity, Entity) {
+    if a <= b { (a, b) } else { (b, a) }
+}
@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    CharacterStats, Collider, CollisionLayers, DeathEvent, GameLoopSchedules, GameState, Health, PickupAssets,
+    Obstacle, PickupKind, PlayerComponent, RunRng, RunScoped, Stat, StaticObjectBundle,
+    direct_waves,
+    LAYER_DESTRUCTIBLE, LAYER_ENEMY, LAYER_PLAYER,
+};
+use super::pickup::spawn_pickup;
+use super::types::{AssetKey, SpawnMesh, ASSET_KEY_DESTRUCTIBLE};
+
+const TORCH_HEALTH: f32 = 8.0;
+const TORCH_COLLIDER_RADIUS: f32 = 0.3;
+// torches are laid out on a grid around the arena center (skipping the center itself)
+const TORCH_GRID_HALF_SIZE: i32 = 3;
+const TORCH_GRID_SPACING: f32 = 12.0;
+
+const TORCH_LIGHT_HEIGHT: f32 = 1.3;
+const TORCH_LIGHT_INTENSITY: f32 = 40_000.0;
+const TORCH_LIGHT_RANGE: f32 = 6.0;
+const TORCH_LIGHT_COLOR: Color = Color::rgb(1.0, 0.6, 0.25);
+
+/// Weighted loot table rolled when a torch breaks, `None` means nothing drops.
+const TORCH_LOOT_TABLE: &[(Option<PickupKind>, u32)] = &[
+    (None, 40),
+    (Some(PickupKind::Health(25.0)), 35),
+    (Some(PickupKind::Magnet), 15),
+    (Some(PickupKind::Bomb), 10),
+];
+
+#[derive(Component, Debug, Default)]
+pub struct Destructible;
+
+#[derive(Component, Debug)]
+struct TorchFlicker {
+    // offsets each torch so they don't all flicker in sync
+    phase: f32,
+}
+
+pub struct DestructiblePlugin;
+impl Plugin for DestructiblePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnExit(GameState::Initialize), spawn_torches)
+            .add_systems(FixedUpdate,
+                flicker_torch_lights
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates)
+            )
+            .add_systems(FixedUpdate,
+                break_destructibles
+                .before(direct_waves)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::Spawn)
+            )
+        ;
+    }
+}
+
+fn spawn_torches(
+    mut commands: Commands,
+    mut events: EventWriter<SpawnMesh>,
+) {
+    let asset_key = AssetKey(ASSET_KEY_DESTRUCTIBLE.into());
+    let mesh_transform = Transform::default().looking_at(-Vec3::Y, Vec3::Z);
+
+    let cells = -TORCH_GRID_HALF_SIZE..=TORCH_GRID_HALF_SIZE;
+    for (n, (x, y)) in cells.clone().flat_map(|x| cells.clone().map(move |y| (x, y))).enumerate() {
+        if x == 0 && y == 0 { continue; }
+        let location = Vec3::new(x as f32, y as f32, 0.0) * TORCH_GRID_SPACING;
+
+        let torch = commands.spawn((
+            StaticObjectBundle {
+                transform: SpatialBundle {
+                    transform: Transform::from_translation(location),
+                    ..default()
+                },
+                collider: Collider::circle(TORCH_COLLIDER_RADIUS),
+                // blocks movement like any other obstacle, see `push_out_of_obstacles`
+                layers: CollisionLayers::new(LAYER_DESTRUCTIBLE, LAYER_PLAYER | LAYER_ENEMY),
+            },
+            Destructible,
+            Obstacle,
+            Health::new(TORCH_HEALTH),
+            asset_key.clone(),
+            RunScoped,
+        )).with_children(|parent| {
+            parent.spawn((
+                PointLightBundle {
+                    point_light: PointLight {
+                        color: TORCH_LIGHT_COLOR,
+                        intensity: TORCH_LIGHT_INTENSITY,
+                        range: TORCH_LIGHT_RANGE,
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0.0, 0.0, TORCH_LIGHT_HEIGHT),
+                    ..default()
+                },
+                TorchFlicker { phase: n as f32 * 1.7 },
+            ));
+        }).id();
+
+        events.send(SpawnMesh(torch, asset_key.clone(), mesh_transform));
+    }
+}
+
+fn flicker_torch_lights(
+    time: Res<Time>,
+    mut q_lights: Query<(&mut PointLight, &TorchFlicker)>,
+) {
+    let t = time.elapsed_seconds();
+    for (mut light, flicker) in q_lights.iter_mut() {
+        // a few out of phase waves is a cheap stand-in for noise
+        let t = t + flicker.phase;
+        let wobble = (t * 7.0).sin() * 0.5 + (t * 13.0).sin() * 0.3 + (t * 23.0).sin() * 0.2;
+        light.intensity = TORCH_LIGHT_INTENSITY * (0.85 + 0.15 * wobble);
+    }
+}
+
+fn break_destructibles(
+    mut commands: Commands,
+    mut deaths: EventReader<DeathEvent>,
+    pickup_assets: Res<PickupAssets>,
+    q_destructibles: Query<(&Transform, Option<&Children>), With<Destructible>>,
+    q_lights: Query<(), With<TorchFlicker>>,
+    q_player: Query<&CharacterStats, With<PlayerComponent>>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    let luck = q_player.get_single().map_or(1.0, |stats| stats.get(Stat::Luck));
+    for event in deaths.read() {
+        let (location, children) = if let Ok(res) = q_destructibles.get(event.0) {
+            res
+        } else { continue; };
+
+        // the flame goes out as soon as it breaks
+        for child in children.into_iter().flatten() {
+            if q_lights.contains(*child) {
+                commands.entity(*child).despawn_recursive();
+            }
+        }
+
+        if let Some(kind) = roll_loot(TORCH_LOOT_TABLE, luck, &mut run_rng.0) {
+            spawn_pickup(kind, location.translation, &pickup_assets, &mut commands);
+        }
+    }
+}
+
+/// `luck` scales the weight of every actual drop, so the `None` entries come up less often.
+fn roll_loot<T: Copy>(table: &[(Option<T>, u32)], luck: f32, rng: &mut impl Rng) -> Option<T> {
+    let weight_of = |(drop, weight): &(Option<T>, u32)| match drop {
+        Some(_) => *weight as f32 * luck.max(0.0),
+        None => *weight as f32,
+    };
+
+    let total: f32 = table.iter().map(weight_of).sum();
+    if total <= 0.0 { return None; }
+
+    let mut roll = rng.gen_range(0.0..total);
+    for entry in table {
+        let weight = weight_of(entry);
+        if roll < weight { return entry.0; }
+        roll -= weight;
+    }
+    None
+}
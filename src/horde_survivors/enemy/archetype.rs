@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use super::SteeringWeights;
+
+pub const ENEMY_ARCHETYPES_PATH: &str = "enemies.ron";
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum EnemyBehaviour {
+    /// walks straight at the player.
+    #[default]
+    Chase,
+    /// keeps about `range` away from the player, shooting at them.
+    Ranged {
+        range: f32,
+        #[serde(default)]
+        attack: RangedAttack,
+    },
+}
+
+/// How a `Ranged` enemy shoots, anything left out uses the defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RangedAttack {
+    /// seconds between shots
+    pub cooldown: f32,
+    /// seconds spent standing still, drawing back, before each shot
+    pub windup: f32,
+    pub projectile_speed: f32,
+    pub damage: f32,
+}
+impl Default for RangedAttack {
+    fn default() -> Self {
+        Self {
+            cooldown: 3.0,
+            windup: 0.6,
+            projectile_speed: 4.0,
+            damage: 6.0,
+        }
+    }
+}
+
+/// One kind of enemy, as defined in `ENEMY_ARCHETYPES_PATH`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnemyArchetype {
+    pub name: String,
+    /// key into the `MeshAssetMap`
+    pub asset_key: String,
+    pub speed: f32,
+    pub health: f32,
+    pub contact_damage: f32,
+    /// seconds between each bit of `contact_damage` while touching the player
+    #[serde(default = "default_contact_interval")]
+    pub contact_interval: f32,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    /// (r, g, b) multiplied into the mesh's materials
+    #[serde(default = "default_tint")]
+    pub tint: (f32, f32, f32),
+    pub xp_value: u32,
+    #[serde(default)]
+    pub behaviour: EnemyBehaviour,
+    #[serde(default)]
+    pub steering: SteeringWeights,
+}
+
+fn default_contact_interval() -> f32 { 1.0 }
+fn default_scale() -> f32 { 1.0 }
+fn default_tint() -> (f32, f32, f32) { (1.0, 1.0, 1.0) }
+
+/// Every `EnemyArchetype`, keyed by the id used to spawn them.
+#[derive(Asset, TypePath, Debug, Deserialize)]
+#[serde(transparent)]
+pub struct EnemyArchetypes(pub HashMap<String, EnemyArchetype>);
+
+#[derive(Resource, Debug, Default)]
+pub struct EnemyArchetypesHandle(pub Handle<EnemyArchetypes>);
+
+
+#[derive(Default)]
+pub struct EnemyArchetypesLoader;
+impl AssetLoader for EnemyArchetypesLoader {
+    type Asset = EnemyArchetypes;
+    type Settings = ();
+    type Error = ArchetypesLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+#[derive(Debug)]
+pub enum ArchetypesLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+impl std::fmt::Display for ArchetypesLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArchetypesLoaderError::Io(e) => write!(f, "could not read enemy archetypes: {}", e),
+            ArchetypesLoaderError::Ron(e) => write!(f, "could not parse enemy archetypes: {}", e),
+        }
+    }
+}
+impl std::error::Error for ArchetypesLoaderError {}
+impl From<std::io::Error> for ArchetypesLoaderError {
+    fn from(e: std::io::Error) -> Self { Self::Io(e) }
+}
+impl From<ron::error::SpannedError> for ArchetypesLoaderError {
+    fn from(e: ron::error::SpannedError) -> Self { Self::Ron(e) }
+}
@@ -0,0 +1,294 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    CollisionLayers, DeathEvent, Dying, GameLoopSchedules, GameState, Health, PickupAssets, PickupKind,
+    PlayerComponent, ProjectileStats, RunRng, Velocity, WaveDirector,
+    reset_run_resource, update_velocity,
+    HOSTILE_PROJECTILE_FILTERS, LAYER_PROJECTILE,
+};
+use crate::horde_survivors::{pickup::spawn_pickup, projectile::spawn_projectile};
+use crate::horde_survivors::types::{AnimationType, SpawnMesh, TriggerAnimation};
+use crate::horde_survivors::ui::boss::BossUiPlugin;
+use super::{spawn_enemy, EnemyArchetypes, EnemyArchetypesHandle, EnemyMovement};
+
+const BOSS_ARCHETYPE: &str = "skeleton_lord";
+/// run times (seconds) a boss shows up at
+const BOSS_SCHEDULE: &[f32] = &[300.0, 600.0, 900.0, 1200.0, 1500.0, 1800.0];
+/// how long before a boss spawns it is announced
+const BOSS_WARNING_TIME: f32 = 5.0;
+const BOSS_SPAWN_DIST: f32 = 14.0;
+/// each boss after the first has this much more health than the last
+const BOSS_HEALTH_GROWTH: f32 = 0.5;
+/// bonus upgrades in the chest a boss drops
+const BOSS_CHEST_UPGRADES: u32 = 2;
+
+const SUMMON_ARCHETYPE: &str = "skeleton";
+const SUMMON_COUNT: usize = 4;
+const SUMMON_RADIUS: f32 = 2.5;
+
+const CHARGE_WINDUP: f32 = 0.75;
+const CHARGE_DURATION: f32 = 1.0;
+const CHARGE_SPEED_MULT: f32 = 4.0;
+
+const BARRAGE_COUNT: usize = 12;
+const BARRAGE_STATS: ProjectileStats = ProjectileStats {
+    speed: 6.0,
+    lifetime: 3.0,
+    pierce: 0,
+    damage: 10.0,
+};
+
+
+/// What a `Boss` does, decided by how much `Health` it has left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BossPhase {
+    /// calls in a pack of skeletons
+    Summon,
+    /// winds up, then dashes at the player
+    Charge,
+    /// fires a ring of projectiles
+    Barrage,
+}
+impl BossPhase {
+    fn from_health(health: &Health) -> Self {
+        let fraction = health.current / health.max;
+        if fraction > 2.0 / 3.0 {
+            BossPhase::Summon
+        } else if fraction > 1.0 / 3.0 {
+            BossPhase::Charge
+        } else {
+            BossPhase::Barrage
+        }
+    }
+
+    /// seconds between each use of the phase's attack
+    fn interval(&self) -> f32 {
+        match self {
+            BossPhase::Summon => 6.0,
+            BossPhase::Charge => 4.0,
+            BossPhase::Barrage => 2.5,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Boss {
+    pub name: String,
+    pub phase: BossPhase,
+    attack: Timer,
+}
+
+/// A `Boss` mid charge, `follow_player` leaves it alone until it's done.
+#[derive(Component, Debug)]
+pub struct Charging {
+    windup: Timer,
+    dash: Timer,
+    direction: Vec2,
+}
+
+/// Sent `BOSS_WARNING_TIME` seconds before a boss spawns.
+#[derive(Event, Debug, Clone)]
+pub struct BossIncoming(pub String);
+
+/// Which of the `BOSS_SCHEDULE` entries have been handled.
+#[derive(Resource, Debug, Default)]
+struct BossSchedule {
+    next: usize,
+    warned: bool,
+}
+
+
+pub struct BossPlugin;
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(BossUiPlugin)
+
+            // Resources
+            .insert_resource(BossSchedule::default())
+
+            // Events
+            .add_event::<BossIncoming>()
+
+            // Systems
+            .add_systems(OnEnter(GameState::Initialize), reset_run_resource::<BossSchedule>)
+            .add_systems(FixedUpdate,
+                (schedule_bosses, drop_boss_chests)
+                .after(super::direct_waves)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::Spawn)
+            )
+            .add_systems(FixedUpdate,
+                (update_boss_phases, boss_attacks, charge)
+                .chain()
+                .after(super::follow_player)
+                .before(update_velocity)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates)
+            )
+        ;
+    }
+}
+
+fn schedule_bosses(
+    director: Res<WaveDirector>,
+    mut schedule: ResMut<BossSchedule>,
+    mut run_rng: ResMut<RunRng>,
+    q_player: Query<&Transform, With<PlayerComponent>>,
+    archetypes_handle: Res<EnemyArchetypesHandle>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
+
+    mut commands: Commands,
+    mut meshes: EventWriter<SpawnMesh>,
+    mut announcements: EventWriter<BossIncoming>,
+) {
+    let spawn_at = if let Some(time) = BOSS_SCHEDULE.get(schedule.next) {
+        *time
+    } else { return; };
+    let archetype = if let Some(res) = archetypes.get(&archetypes_handle.0) {
+        res
+    } else { return; };
+    let name = archetype.0.get(BOSS_ARCHETYPE).map_or(BOSS_ARCHETYPE.into(), |a| a.name.clone());
+
+    if !schedule.warned && director.elapsed >= spawn_at - BOSS_WARNING_TIME {
+        info!("{} incoming", name);
+        announcements.send(BossIncoming(name.clone()));
+        schedule.warned = true;
+    }
+    if director.elapsed < spawn_at { return; }
+
+    let center = if let Ok(t) = q_player.get_single() {
+        t.translation
+    } else { return; };
+
+    let offset = Vec2::from_angle(run_rng.0.gen_range(0.0..TAU)) * BOSS_SPAWN_DIST;
+    let spawn_pt = Transform::from_translation(center + offset.extend(0.0));
+    if let Some(boss) = spawn_enemy(BOSS_ARCHETYPE, spawn_pt, archetype, &mut commands, &mut meshes) {
+        let base_health = archetype.0[BOSS_ARCHETYPE].health;
+        let phase = BossPhase::Summon;
+        commands.entity(boss).insert((
+            Health::new(base_health * (1.0 + BOSS_HEALTH_GROWTH * schedule.next as f32)),
+            Boss {
+                name,
+                phase,
+                attack: Timer::from_seconds(phase.interval(), TimerMode::Repeating),
+            },
+        ));
+    }
+
+    schedule.next += 1;
+    schedule.warned = false;
+}
+
+fn update_boss_phases(
+    mut q_bosses: Query<(Entity, &mut Boss, &Health), (Changed<Health>, Without<Dying>)>,
+    mut animations: EventWriter<TriggerAnimation>,
+) {
+    for (entity, mut boss, health) in q_bosses.iter_mut() {
+        let phase = BossPhase::from_health(health);
+        if phase == boss.phase { continue; }
+
+        info!("{} enters its {:?} phase", boss.name, phase);
+        boss.phase = phase;
+        boss.attack = Timer::from_seconds(phase.interval(), TimerMode::Repeating);
+        animations.send(TriggerAnimation(entity, AnimationType::TakeHit));
+    }
+}
+
+fn boss_attacks(
+    time: Res<Time>,
+    mut q_bosses: Query<(Entity, &Transform, &mut Boss), (Without<Dying>, Without<Charging>)>,
+    q_player: Query<&Transform, With<PlayerComponent>>,
+    archetypes_handle: Res<EnemyArchetypesHandle>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
+
+    mut commands: Commands,
+    mut meshes: EventWriter<SpawnMesh>,
+) {
+    let player_loc = if let Ok(t) = q_player.get_single() {
+        t.translation
+    } else { return; };
+    let archetypes = if let Some(res) = archetypes.get(&archetypes_handle.0) {
+        res
+    } else { return; };
+
+    for (entity, transform, mut boss) in q_bosses.iter_mut() {
+        if !boss.attack.tick(time.delta()).just_finished() { continue; }
+
+        let origin = transform.translation;
+        match boss.phase {
+            BossPhase::Summon => {
+                let step = TAU / SUMMON_COUNT as f32;
+                for n in 0..SUMMON_COUNT {
+                    let offset = Vec2::from_angle(step * n as f32) * SUMMON_RADIUS;
+                    let spawn_pt = Transform::from_translation(origin + offset.extend(0.0));
+                    spawn_enemy(SUMMON_ARCHETYPE, spawn_pt, archetypes, &mut commands, &mut meshes);
+                }
+            },
+            BossPhase::Charge => {
+                let direction = (player_loc - origin).truncate().normalize_or_zero();
+                commands.entity(entity).insert(Charging {
+                    windup: Timer::from_seconds(CHARGE_WINDUP, TimerMode::Once),
+                    dash: Timer::from_seconds(CHARGE_DURATION, TimerMode::Once),
+                    direction,
+                });
+            },
+            BossPhase::Barrage => {
+                let step = TAU / BARRAGE_COUNT as f32;
+                for n in 0..BARRAGE_COUNT {
+                    spawn_projectile(
+                        origin,
+                        Vec2::from_angle(step * n as f32),
+                        BARRAGE_STATS,
+                        CollisionLayers::new(LAYER_PROJECTILE, HOSTILE_PROJECTILE_FILTERS),
+                        &mut commands,
+                        &mut meshes,
+                    );
+                }
+            },
+        }
+    }
+}
+
+fn charge(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_charging: Query<(Entity, &mut Charging, &EnemyMovement, &mut Velocity), Without<Dying>>,
+    mut animations: EventWriter<TriggerAnimation>,
+) {
+    for (entity, mut charging, movement, mut velocity) in q_charging.iter_mut() {
+        // stand still and telegraph it first
+        if !charging.windup.tick(time.delta()).finished() {
+            velocity.0 = Vec3::ZERO;
+            animations.send(TriggerAnimation(entity, AnimationType::Idle));
+            continue;
+        }
+
+        if charging.dash.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Charging>();
+            continue;
+        }
+
+        velocity.0 = (charging.direction * movement.speed * CHARGE_SPEED_MULT).extend(0.0);
+        animations.send(TriggerAnimation(entity, AnimationType::Run));
+    }
+}
+
+fn drop_boss_chests(
+    mut commands: Commands,
+    mut deaths: EventReader<DeathEvent>,
+    pickup_assets: Res<PickupAssets>,
+    q_bosses: Query<(&Transform, &Boss)>,
+) {
+    for event in deaths.read() {
+        let (location, boss) = if let Ok(res) = q_bosses.get(event.0) {
+            res
+        } else { continue; };
+
+        info!("{} defeated", boss.name);
+        spawn_pickup(PickupKind::Chest(BOSS_CHEST_UPGRADES), location.translation, &pickup_assets, &mut commands);
+    }
+}
@@ -0,0 +1,189 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{PlayerComponent, PlayerExperience, RunRng};
+use super::{spawn_enemy, EnemyArchetypes, EnemyArchetypesHandle, EnemyComponent, SpawnMesh};
+
+/// just outside of what the camera can see
+const SPAWN_DIST: f32 = 15.0;
+/// `SpawnPattern::RandomOffscreen` scatters spawns this much further out
+const SPAWN_DIST_JITTER: f32 = 5.0;
+const ARC_SPREAD: f32 = FRAC_PI_2;
+const CLUSTER_RADIUS: f32 = 2.0;
+const LINE_SPACING: f32 = 1.25;
+
+// spawn pressure, on top of the timeline
+const PRESSURE_PER_MINUTE: f32 = 0.05;
+const PRESSURE_PER_LEVEL: f32 = 0.03;
+/// stop adding to the horde past this many living enemies
+const MAX_ENEMIES: usize = 400;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnPattern {
+    /// evenly spaced all the way around the player
+    Ring,
+    /// bunched together on one side of the player
+    Arc,
+    /// a tight pack at a single point
+    Cluster,
+    /// a wall marching in from one direction
+    LineSweep,
+    /// scattered at random points around the player
+    RandomOffscreen,
+}
+
+/// One entry in the `WAVE_TIMELINE`, in effect from `start` until the next stage starts.
+#[derive(Debug)]
+struct WaveStage {
+    /// seconds into the run
+    start: f32,
+    /// seconds between each group of spawns
+    interval: f32,
+    /// enemies per group, before spawn pressure is applied
+    group_size: u32,
+    /// weighted enemy archetype ids to pick from
+    pool: &'static [(&'static str, u32)],
+    patterns: &'static [SpawnPattern],
+}
+
+use SpawnPattern::*;
+const WAVE_TIMELINE: &[WaveStage] = &[
+    WaveStage { start: 0.0, interval: 5.0, group_size: 6,
+        pool: &[("skeleton", 1)],
+        patterns: &[Ring, RandomOffscreen] },
+    WaveStage { start: 60.0, interval: 4.5, group_size: 8,
+        pool: &[("skeleton", 3), ("skeleton_runner", 1)],
+        patterns: &[Ring, Arc, RandomOffscreen] },
+    WaveStage { start: 180.0, interval: 4.0, group_size: 10,
+        pool: &[("skeleton", 3), ("skeleton_runner", 2), ("skeleton_archer", 1)],
+        patterns: &[Ring, Arc, Cluster, RandomOffscreen] },
+    WaveStage { start: 360.0, interval: 3.5, group_size: 12,
+        pool: &[("skeleton", 3), ("skeleton_runner", 2), ("skeleton_archer", 1), ("skeleton_brute", 1)],
+        patterns: &[Ring, Arc, Cluster, LineSweep, RandomOffscreen] },
+    WaveStage { start: 600.0, interval: 3.0, group_size: 14,
+        pool: &[("skeleton", 2), ("skeleton_runner", 2), ("skeleton_archer", 2), ("skeleton_brute", 1)],
+        patterns: &[Ring, Arc, Cluster, LineSweep, RandomOffscreen] },
+    WaveStage { start: 900.0, interval: 2.5, group_size: 16,
+        pool: &[("skeleton", 2), ("skeleton_runner", 2), ("skeleton_archer", 2), ("skeleton_brute", 2)],
+        patterns: &[Ring, Arc, Cluster, LineSweep] },
+    WaveStage { start: 1200.0, interval: 2.0, group_size: 20,
+        pool: &[("skeleton", 1), ("skeleton_runner", 2), ("skeleton_archer", 2), ("skeleton_brute", 3)],
+        patterns: &[Ring, Cluster, LineSweep] },
+    WaveStage { start: 1500.0, interval: 1.5, group_size: 24,
+        pool: &[("skeleton_runner", 2), ("skeleton_archer", 2), ("skeleton_brute", 3)],
+        patterns: &[Ring, Cluster, LineSweep] },
+];
+
+fn stage_at(elapsed: f32) -> &'static WaveStage {
+    WAVE_TIMELINE.iter()
+        .rev()
+        .find(|stage| elapsed >= stage.start)
+        .unwrap_or(&WAVE_TIMELINE[0])
+}
+
+/// Tracks how far into the run we are, and when the next group of enemies is due.
+#[derive(Resource, Debug)]
+pub struct WaveDirector {
+    /// seconds of `Playing` time so far
+    pub elapsed: f32,
+    next_spawn: Timer,
+}
+impl Default for WaveDirector {
+    fn default() -> Self {
+        Self {
+            elapsed: 0.0,
+            next_spawn: Timer::from_seconds(WAVE_TIMELINE[0].interval, TimerMode::Once),
+        }
+    }
+}
+
+/// multiplier on group sizes, ramping with time (on top of the timeline) and the player's level.
+fn spawn_pressure(elapsed: f32, player_level: u32) -> f32 {
+    1.0 + (elapsed / 60.0) * PRESSURE_PER_MINUTE + player_level.saturating_sub(1) as f32 * PRESSURE_PER_LEVEL
+}
+
+pub fn direct_waves(
+    time: Res<Time>,
+    mut director: ResMut<WaveDirector>,
+    mut run_rng: ResMut<RunRng>,
+    q_player: Query<(&Transform, &PlayerExperience), With<PlayerComponent>>,
+    q_enemies: Query<(), With<EnemyComponent>>,
+    archetypes_handle: Res<EnemyArchetypesHandle>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
+
+    mut commands: Commands,
+    mut events: EventWriter<SpawnMesh>,
+) {
+    director.elapsed += time.delta_seconds();
+    if !director.next_spawn.tick(time.delta()).just_finished() { return; }
+
+    let stage = stage_at(director.elapsed);
+    director.next_spawn = Timer::from_seconds(stage.interval, TimerMode::Once);
+
+    let (center, experience) = if let Ok((t, xp)) = q_player.get_single() {
+        (t.translation, xp)
+    } else { return; };
+    let archetypes = if let Some(res) = archetypes.get(&archetypes_handle.0) {
+        res
+    } else { return; };
+
+    let room = MAX_ENEMIES.saturating_sub(q_enemies.iter().count());
+    let count = (stage.group_size as f32 * spawn_pressure(director.elapsed, experience.level)).round() as usize;
+    let count = count.min(room);
+    if count == 0 { return; }
+
+    let rng = &mut run_rng.0;
+    let pattern = *stage.patterns.choose(rng).unwrap_or(&Ring);
+    debug!("{:.0}s: spawning {} enemies in a {:?}", director.elapsed, count, pattern);
+
+    for offset in spawn_points(pattern, count, rng) {
+        let id = if let Ok((id, _)) = stage.pool.choose_weighted(rng, |(_, weight)| *weight) {
+            id
+        } else { return; };
+
+        let spawn_pt = Transform::from_translation(center + offset.extend(0.0));
+        spawn_enemy(id, spawn_pt, archetypes, &mut commands, &mut events);
+    }
+}
+
+/// `count` offsets from the player, laid out in `pattern`.
+fn spawn_points(pattern: SpawnPattern, count: usize, rng: &mut impl Rng) -> Vec<Vec2> {
+    let heading = Vec2::from_angle(rng.gen_range(0.0..TAU));
+
+    match pattern {
+        SpawnPattern::Ring => {
+            let step = TAU / count as f32;
+            (0..count)
+                .map(|n| Vec2::from_angle(step * n as f32).rotate(heading) * SPAWN_DIST)
+                .collect()
+        },
+        SpawnPattern::Arc => {
+            let step = ARC_SPREAD / count.max(2).saturating_sub(1) as f32;
+            (0..count)
+                .map(|n| Vec2::from_angle(step * n as f32 - ARC_SPREAD * 0.5).rotate(heading) * SPAWN_DIST)
+                .collect()
+        },
+        SpawnPattern::Cluster => {
+            let at = heading * SPAWN_DIST;
+            (0..count)
+                .map(|_| at + Vec2::from_angle(rng.gen_range(0.0..TAU)) * rng.gen_range(0.0..CLUSTER_RADIUS))
+                .collect()
+        },
+        SpawnPattern::LineSweep => {
+            // centered on `heading`, running across it
+            let across = heading.perp();
+            let first = -(count.saturating_sub(1) as f32) * LINE_SPACING * 0.5;
+            (0..count)
+                .map(|n| heading * SPAWN_DIST + across * (first + LINE_SPACING * n as f32))
+                .collect()
+        },
+        SpawnPattern::RandomOffscreen => {
+            (0..count)
+                .map(|_| Vec2::from_angle(rng.gen_range(0.0..TAU)) * (SPAWN_DIST + rng.gen_range(0.0..SPAWN_DIST_JITTER)))
+                .collect()
+        },
+    }
+}
@@ -0,0 +1,261 @@
+use bevy::{asset::LoadState, prelude::*};
+
+use crate::{
+    Collider, CollisionEnded, CollisionLayers, CollisionStarted, DamageEvent, Dying, ExperienceDrop, GameLoopSchedules, GameState, Health,
+    MovableObjectBundle, PlayerComponent, PlayerFlowField, RunScoped, SpatialIndex, StatusEffects, Velocity,
+    apply_health_stats, collect_pickups, reset_run_resource, tick_status_effects, update_velocity,
+    LAYER_ENEMY, LAYER_PLAYER,
+};
+
+use super::LoadingAssets;
+use super::types::{AnimationSpeed, AnimationType, AssetKey, MeshTint, SpawnMesh, TriggerAnimation};
+
+mod archetype;
+mod boss;
+mod director;
+mod ranged;
+mod steering;
+pub use archetype::*;
+pub use boss::*;
+pub use director::*;
+pub use ranged::*;
+pub use steering::SteeringWeights;
+use steering::{steer, Steerer};
+
+// at a `scale` of 1.0
+const ENEMY_COLLIDER_RADIUS: f32 = 0.4;
+/// chasers aim to overlap the player by this much, just touching wouldn't count as a collision
+const CHASE_OVERLAP: f32 = 0.15;
+/// ranged enemies back off from the player once they're closer than this fraction of their range
+const RANGED_KEEP_AWAY_FRACTION: f32 = 0.6;
+
+#[derive(Component, Debug, Default)]
+pub struct EnemyComponent;
+
+/// The display name of the enemy's `EnemyArchetype`.
+#[derive(Component, Debug, Clone)]
+pub struct EnemyName(pub String);
+
+/// How an enemy moves, copied from its `EnemyArchetype` when spawned.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct EnemyMovement {
+    pub speed: f32,
+    pub behaviour: EnemyBehaviour,
+    pub steering: SteeringWeights,
+}
+
+/// Damage dealt to the player on contact, at most once every `interval` seconds.
+#[derive(Component, Debug, Clone)]
+pub struct ContactDamage {
+    pub amount: f32,
+    cooldown: Timer,
+    /// overlapping the player, kept up to date by `track_player_contacts`
+    touching: bool,
+}
+impl ContactDamage {
+    pub fn new(amount: f32, interval: f32) -> Self {
+        // ready to go, the first touch always lands
+        let mut cooldown = Timer::from_seconds(interval, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Self { amount, cooldown, touching: false }
+    }
+}
+
+pub struct EnemyPlugin;
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(BossPlugin)
+
+            // Assets
+            .init_asset::<EnemyArchetypes>()
+            .init_asset_loader::<EnemyArchetypesLoader>()
+
+            // Resources
+            .insert_resource(WaveDirector::default())
+            .insert_resource(EnemyArchetypesHandle::default())
+            
+            // Systems
+            .add_systems(Startup, load_enemy_archetypes)
+            .add_systems(OnEnter(GameState::Initialize), reset_run_resource::<WaveDirector>)
+            .add_systems(FixedUpdate, 
+                direct_waves
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::Spawn)
+            )
+            .add_systems(FixedUpdate, 
+                follow_player
+                .after(apply_health_stats)
+                .after(tick_status_effects)
+                .before(update_velocity)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates)
+            )
+            .add_systems(FixedUpdate,
+                (start_ranged_attacks, release_ranged_attacks)
+                .chain()
+                .after(follow_player)
+                .before(update_velocity)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates)
+            )
+            .add_systems(FixedUpdate,
+                (track_player_contacts, deal_contact_damage)
+                .chain()
+                .after(collect_pickups)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::CollisionDetection)
+            )
+        ;
+    }
+}
+
+fn load_enemy_archetypes(
+    mut handle: ResMut<EnemyArchetypesHandle>,
+    mut loading_assets: ResMut<LoadingAssets>,
+    asset_server: Res<AssetServer>,
+) {
+    handle.0 = asset_server.load(ENEMY_ARCHETYPES_PATH);
+    loading_assets.0.insert(handle.0.clone_weak().untyped().id(), LoadState::NotLoaded);
+}
+
+/// Spawns the enemy `archetype_id` from `archetypes`, returns `None` if there is no such archetype.
+pub(crate) fn spawn_enemy(
+    archetype_id: &str,
+    spawn_pt: Transform,
+    archetypes: &EnemyArchetypes,
+    commands: &mut Commands,
+    events: &mut EventWriter<SpawnMesh>,
+) -> Option<Entity> {
+    let archetype = if let Some(res) = archetypes.0.get(archetype_id) {
+        res
+    } else {
+        warn!("no enemy archetype: {:?}", archetype_id);
+        return None;
+    };
+    info!("spawn {} at: {:?}", archetype.name, spawn_pt.translation);
+    
+    let enemy_asset_key = AssetKey(archetype.asset_key.clone());
+    let (r, g, b) = archetype.tint;
+
+    let enemy = commands.spawn((
+        MovableObjectBundle{
+            transform:SpatialBundle { transform: spawn_pt, ..default() },
+            collider: Collider::circle(ENEMY_COLLIDER_RADIUS * archetype.scale),
+            layers: CollisionLayers::new(LAYER_ENEMY, LAYER_PLAYER),
+            ..default()
+        },
+        EnemyComponent,
+        EnemyName(archetype.name.clone()),
+        EnemyMovement {
+            speed: archetype.speed,
+            behaviour: archetype.behaviour,
+            steering: archetype.steering,
+        },
+        ContactDamage::new(archetype.contact_damage, archetype.contact_interval),
+        Health::new(archetype.health),
+        StatusEffects::default(),
+        AnimationSpeed::default(),
+        ExperienceDrop(archetype.xp_value),
+        MeshTint(Color::rgb(r, g, b)),
+        enemy_asset_key.clone(),
+        RunScoped,
+    )).id();
+
+    let t = Transform::default()
+    .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2))
+    .looking_at(-Vec3::Y, Vec3::Z)
+    .with_scale(Vec3::splat(archetype.scale));
+
+    if let EnemyBehaviour::Ranged { range, attack } = archetype.behaviour {
+        commands.entity(enemy).insert(RangedAttacker::new(range, attack));
+    }
+
+    events.send(SpawnMesh(enemy, enemy_asset_key, t));
+    Some(enemy)
+}
+
+fn follow_player(
+    mut q_enemy: Query<(Entity, &Transform, &Collider, &EnemyMovement, &mut Velocity), (With<EnemyComponent>, Without<Dying>, Without<Charging>, Without<AttackWindup>)>,
+    q_player: Query<(&Transform, &Collider), With<PlayerComponent>>,
+    index: Res<SpatialIndex>,
+    flow_field: Res<PlayerFlowField>,
+    
+    mut events: EventWriter<TriggerAnimation>,
+) {
+
+    let (player_loc, player_collider) = if let Ok(res) = q_player.get_single() {
+        res
+    } else { return; };
+    let target = player_loc.translation.truncate();
+
+    for (entity, enemy_loc, collider, movement, mut enemy_velocity) in q_enemy.iter_mut() {
+        let steerer = Steerer {
+            entity,
+            position: enemy_loc.translation.truncate(),
+            radius: collider.bounding_radius(),
+            speed: movement.speed,
+            weights: movement.steering,
+        };
+
+        let (stop_dist, keep_away) = match movement.behaviour {
+            // close enough to touch, separation spreads the rest of the horde around the player
+            EnemyBehaviour::Chase => (player_collider.bounding_radius() + steerer.radius - CHASE_OVERLAP, 0.0),
+            EnemyBehaviour::Ranged { range, .. } => (range, range * RANGED_KEEP_AWAY_FRACTION),
+        };
+
+        let flow = flow_field.0.direction_at(steerer.position);
+        let move_vec = steer(&steerer, target, stop_dist, keep_away, flow, &index.0);
+        enemy_velocity.0.x = move_vec.x;
+        enemy_velocity.0.y = move_vec.y;
+
+        let next_animation = if move_vec.length_squared() > 0.05 {
+            AnimationType::Walk
+        } else {
+            AnimationType::Idle
+        };
+        events.send(TriggerAnimation(entity, next_animation));
+    }
+}
+
+fn track_player_contacts(
+    mut started: EventReader<CollisionStarted>,
+    mut ended: EventReader<CollisionEnded>,
+    q_player: Query<(), With<PlayerComponent>>,
+    mut q_enemies: Query<&mut ContactDamage>,
+) {
+    let started = started.read().map(|e| (e.0, e.1, true));
+    let ended = ended.read().map(|e| (e.0, e.1, false));
+    for (a, b, touching) in started.chain(ended) {
+        let enemy = if q_player.contains(a) {
+            b
+        } else if q_player.contains(b) {
+            a
+        } else { continue; };
+
+        if let Ok(mut contact) = q_enemies.get_mut(enemy) {
+            contact.touching = touching;
+        }
+    }
+}
+
+fn deal_contact_damage(
+    time: Res<Time>,
+    q_player: Query<Entity, (With<PlayerComponent>, Without<Dying>)>,
+    mut q_enemies: Query<(Entity, &mut ContactDamage, Option<&StatusEffects>), Without<Dying>>,
+    mut damage: EventWriter<DamageEvent>,
+) {
+    let player = if let Ok(entity) = q_player.get_single() {
+        entity
+    } else { return; };
+
+    for (entity, mut contact, effects) in q_enemies.iter_mut() {
+        // runs down whether it's touching or not, so closing back in hits right away
+        if !contact.cooldown.tick(time.delta()).finished() { continue; }
+        if effects.is_some_and(|e| e.is_incapacitated()) { continue; }
+        if !contact.touching { continue; }
+
+        contact.cooldown.reset();
+        damage.send(DamageEvent { target: player, amount: contact.amount, source: Some(entity), weapon: None });
+    }
+}
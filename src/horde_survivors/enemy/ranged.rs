@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+
+use crate::{
+    CollisionLayers, Dying, OnHitStatus, PlayerComponent, ProjectileStats, StatusEffect, StatusEffects, StatusKind, Velocity,
+    HOSTILE_PROJECTILE_FILTERS, LAYER_PROJECTILE,
+};
+use crate::horde_survivors::projectile::spawn_projectile;
+use crate::horde_survivors::types::{AnimationType, MeshTint, SpawnMesh, TriggerAnimation};
+use super::RangedAttack;
+
+/// shots only start being lined up within this much of the attacker's range
+const ATTACK_RANGE_SLACK: f32 = 1.5;
+/// projectiles keep going this far past the attacker's range before they despawn
+const PROJECTILE_RANGE_MULT: f32 = 2.0;
+const PROJECTILE_TINT: Color = Color::rgb(1.0, 0.35, 0.35);
+/// getting shot slows the player by this fraction, for `PROJECTILE_SLOW_DURATION` seconds
+const PROJECTILE_SLOW: f32 = 0.3;
+const PROJECTILE_SLOW_DURATION: f32 = 1.5;
+
+
+/// Shoots at the player, added to every `EnemyBehaviour::Ranged` enemy.
+#[derive(Component, Debug)]
+pub struct RangedAttacker {
+    range: f32,
+    attack: RangedAttack,
+    cooldown: Timer,
+}
+impl RangedAttacker {
+    pub fn new(range: f32, attack: RangedAttack) -> Self {
+        Self {
+            range,
+            attack,
+            cooldown: Timer::from_seconds(attack.cooldown, TimerMode::Repeating),
+        }
+    }
+}
+
+/// A `RangedAttacker` standing still, lining up a shot. `follow_player` leaves it alone until it fires.
+#[derive(Component, Debug)]
+pub struct AttackWindup(Timer);
+
+
+pub(super) fn start_ranged_attacks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_attackers: Query<
+        (Entity, &mut Transform, &mut RangedAttacker, &mut Velocity, Option<&StatusEffects>),
+        (Without<Dying>, Without<AttackWindup>, Without<PlayerComponent>),
+    >,
+    q_player: Query<&Transform, With<PlayerComponent>>,
+    mut animations: EventWriter<TriggerAnimation>,
+) {
+    let player_loc = if let Ok(t) = q_player.get_single() {
+        t.translation.truncate()
+    } else { return; };
+
+    for (entity, mut transform, mut attacker, mut velocity, effects) in q_attackers.iter_mut() {
+        if effects.is_some_and(|e| e.is_incapacitated()) { continue; }
+
+        // the cooldown only runs down while the player is (about) in range
+        let to_player = player_loc - transform.translation.truncate();
+        if to_player.length() > attacker.range + ATTACK_RANGE_SLACK { continue; }
+        if !attacker.cooldown.tick(time.delta()).just_finished() { continue; }
+
+        // stop and turn to face the player, same as `update_facing` would
+        velocity.0 = Vec3::ZERO;
+        transform.rotation = Quat::from_rotation_z(-f32::atan2(to_player.x, to_player.y));
+
+        commands.entity(entity).insert(AttackWindup(Timer::from_seconds(attacker.attack.windup, TimerMode::Once)));
+        animations.send(TriggerAnimation(entity, AnimationType::Attack));
+    }
+}
+
+pub(super) fn release_ranged_attacks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_winding_up: Query<
+        (Entity, &Transform, &RangedAttacker, &mut AttackWindup, &mut Velocity, Option<&StatusEffects>),
+        Without<Dying>,
+    >,
+    q_player: Query<&Transform, With<PlayerComponent>>,
+    mut meshes: EventWriter<SpawnMesh>,
+) {
+    let player_loc = if let Ok(t) = q_player.get_single() {
+        t.translation
+    } else { return; };
+
+    for (entity, transform, attacker, mut windup, mut velocity, effects) in q_winding_up.iter_mut() {
+        velocity.0 = Vec3::ZERO;
+        // a stun or freeze holds the shot, rather than cancelling it
+        if effects.is_some_and(|e| e.is_incapacitated()) { continue; }
+        if !windup.0.tick(time.delta()).finished() { continue; }
+
+        commands.entity(entity).remove::<AttackWindup>();
+
+        // aimed wherever the player is now, they can still step out of the way
+        let direction = (player_loc - transform.translation).truncate().normalize_or_zero();
+        if direction == Vec2::ZERO { continue; }
+
+        let speed = attacker.attack.projectile_speed;
+        let projectile = spawn_projectile(
+            transform.translation,
+            direction,
+            ProjectileStats {
+                speed,
+                lifetime: attacker.range * PROJECTILE_RANGE_MULT / speed,
+                pierce: 0,
+                damage: attacker.attack.damage,
+            },
+            CollisionLayers::new(LAYER_PROJECTILE, HOSTILE_PROJECTILE_FILTERS),
+            &mut commands,
+            &mut meshes,
+        );
+        commands.entity(projectile).insert((
+            MeshTint(PROJECTILE_TINT),
+            OnHitStatus(StatusEffect::new(StatusKind::Slow(PROJECTILE_SLOW), PROJECTILE_SLOW_DURATION)),
+        ));
+    }
+}
@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{SpatialGrid, LAYER_ENEMY};
+
+/// extra space enemies try to keep between their colliders
+const SEPARATION_PADDING: f32 = 0.3;
+/// how far away other enemies still count towards cohesion
+const COHESION_RADIUS: f32 = 3.0;
+/// `arrive` starts slowing down this far out from where it wants to stop
+const ARRIVE_SLOWING_DIST: f32 = 1.5;
+/// closer than this, head straight for the target rather than following the flow field's cells
+const DIRECT_SEEK_DIST: f32 = 2.0;
+
+
+/// How strongly each steering behaviour pulls on an enemy, set per archetype.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SteeringWeights {
+    /// towards the player, easing off as it arrives
+    pub seek: f32,
+    /// away from enemies it's overlapping (or about to)
+    pub separation: f32,
+    /// towards the middle of nearby enemies
+    pub cohesion: f32,
+}
+impl Default for SteeringWeights {
+    fn default() -> Self {
+        Self {
+            seek: 1.0,
+            separation: 1.5,
+            cohesion: 0.2,
+        }
+    }
+}
+
+/// Everything about one enemy that `steer` needs.
+pub(super) struct Steerer {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub radius: f32,
+    pub speed: f32,
+    pub weights: SteeringWeights,
+}
+
+/// Blends seek/arrive, separation and cohesion into a velocity (capped at `speed`), that gets the
+/// enemy to within `stop_dist` of `target` without piling up on the others doing the same.
+/// Closer than `keep_away` it backs off again (0 to never back off).
+///
+/// `flow` is the way to head to reach `target` around any obstacles (from a `FlowField`), if known.
+pub(super) fn steer(
+    steerer: &Steerer,
+    target: Vec2,
+    stop_dist: f32,
+    keep_away: f32,
+    flow: Option<Vec2>,
+    grid: &SpatialGrid,
+) -> Vec2 {
+    let weights = steerer.weights;
+
+    // ===== Seek / Arrive / Retreat =====
+    let to_target = target - steerer.position;
+    let dist = to_target.length();
+    let arrive = if dist < keep_away {
+        // negative, so `seek` points away from the target
+        -((keep_away - dist) / ARRIVE_SLOWING_DIST).clamp(0.0, 1.0)
+    } else {
+        ((dist - stop_dist) / ARRIVE_SLOWING_DIST).clamp(0.0, 1.0)
+    };
+    let heading = match flow {
+        // the flow field only ever leads towards the target
+        Some(dir) if dist > DIRECT_SEEK_DIST && arrive > 0.0 => dir,
+        _ => to_target.normalize_or_zero(),
+    };
+    let seek = heading * arrive;
+
+    // ===== Separation & Cohesion =====
+    let mut separation = Vec2::ZERO;
+    let mut neighbours_center = Vec2::ZERO;
+    let mut neighbours = 0;
+
+    let search_radius = COHESION_RADIUS.max(steerer.radius + grid.max_radius() + SEPARATION_PADDING);
+    grid.for_each_in_radius(steerer.position, search_radius, LAYER_ENEMY, |entry| {
+        if entry.entity == steerer.entity { return; }
+
+        let away = steerer.position - entry.position;
+        let gap = away.length();
+        let wanted_gap = steerer.radius + entry.radius + SEPARATION_PADDING;
+        if gap < wanted_gap {
+            // stacked exactly on top of each other, split them along something consistent
+            let dir = if gap > f32::EPSILON {
+                away / gap
+            } else if steerer.entity < entry.entity { Vec2::X } else { -Vec2::X };
+            separation += dir * (1.0 - gap / wanted_gap);
+        }
+
+        if gap <= COHESION_RADIUS {
+            neighbours_center += entry.position;
+            neighbours += 1;
+        }
+    });
+
+    let cohesion = if neighbours > 0 {
+        (neighbours_center / neighbours as f32 - steerer.position).normalize_or_zero()
+    } else { Vec2::ZERO };
+
+    let desired = seek * weights.seek
+        + separation * weights.separation
+        + cohesion * weights.cohesion * arrive.max(0.0);
+    desired.clamp_length_max(1.0) * steerer.speed
+}
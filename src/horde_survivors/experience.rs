@@ -0,0 +1,314 @@
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    CharacterStats, DeathEvent, Dying, GameLoopSchedules, GameState, Magnetized, ModifierOp, ModifierSource,
+    Pickup, PickupAssets, PickupKind, PlayerComponent, RunRng, SpatialIndex, Stat, StatModifier, WeaponKind, WeaponSlots,
+    apply_health_stats, gameplay_running, push_out_of_obstacles, reset_run_resource,
+    LAYER_PICKUP,
+};
+use super::pickup::spawn_pickup;
+use super::ui::{hud::HudPlugin, level_up::LevelUpMenuPlugin};
+
+const BASE_XP_TO_LEVEL: u32 = 5;
+const XP_TO_LEVEL_GROWTH: u32 = 5;
+const UPGRADE_CHOICES: usize = 3;
+
+
+/// Experience gems dropped by an `Entity` when it dies.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct ExperienceDrop(pub u32);
+
+#[derive(Component, Debug)]
+pub struct PlayerExperience {
+    pub level: u32,
+    /// experience collected towards the next level.
+    pub current: u32,
+    pub to_next_level: u32,
+    /// level ups that still need an upgrade picked.
+    pub pending_level_ups: u32,
+}
+impl Default for PlayerExperience {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            current: 0,
+            to_next_level: xp_to_level(1),
+            pending_level_ups: 0,
+        }
+    }
+}
+
+fn xp_to_level(level: u32) -> u32 {
+    BASE_XP_TO_LEVEL + (level - 1) * XP_TO_LEVEL_GROWTH
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct GainExperience(pub u32);
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct LevelUp(pub u32);
+
+/// Sent when a chest is picked up, it's worth this many upgrades (without leveling up).
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ChestOpened(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Upgrade {
+    /// a permanent `StatModifier`, see `passive_bonus`
+    Passive(Stat),
+    /// fills an empty weapon slot
+    NewWeapon(WeaponKind),
+    /// levels up a weapon already being carried
+    LevelWeapon(WeaponKind),
+}
+impl Upgrade {
+    const PASSIVES: [Stat; 10] = [
+        Stat::MaxHealth,
+        Stat::Regeneration,
+        Stat::Armor,
+        Stat::MoveSpeed,
+        Stat::Might,
+        Stat::Area,
+        Stat::Cooldown,
+        Stat::ProjectileCount,
+        Stat::MagnetRadius,
+        Stat::Luck,
+    ];
+
+    pub fn description(&self) -> String {
+        match self {
+            Upgrade::Passive(stat) => match passive_bonus(*stat) {
+                ModifierOp::Add(v) => format!("{} +{}", stat.name(), v),
+                ModifierOp::Mul(v) if v < 1.0 => format!("{} -{:.0}%", stat.name(), (1.0 - v) * 100.0),
+                ModifierOp::Mul(v) => format!("{} +{:.0}%", stat.name(), (v - 1.0) * 100.0),
+            },
+            Upgrade::NewWeapon(kind) => format!("New weapon: {}", kind.name()),
+            Upgrade::LevelWeapon(kind) => format!("{} level up", kind.name()),
+        }
+    }
+}
+
+/// how much picking a passive upgrade for `stat` improves it.
+fn passive_bonus(stat: Stat) -> ModifierOp {
+    match stat {
+        Stat::MaxHealth => ModifierOp::Add(20.0),
+        Stat::Regeneration => ModifierOp::Add(0.5),
+        Stat::Armor => ModifierOp::Add(1.0),
+        Stat::MoveSpeed => ModifierOp::Mul(1.1),
+        Stat::Might => ModifierOp::Mul(1.1),
+        Stat::Area => ModifierOp::Mul(1.1),
+        Stat::Cooldown => ModifierOp::Mul(0.92),
+        Stat::ProjectileCount => ModifierOp::Add(1.0),
+        Stat::MagnetRadius => ModifierOp::Add(1.0),
+        Stat::Luck => ModifierOp::Mul(1.1),
+    }
+}
+
+/// The upgrades offered for the level up currently being handled.
+#[derive(Resource, Debug, Default)]
+pub struct UpgradeChoices(pub Vec<Upgrade>);
+
+/// Sent by the level up menu once an upgrade has been picked.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct UpgradeChosen(pub Upgrade);
+
+
+pub struct ExperiencePlugin;
+impl Plugin for ExperiencePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins((HudPlugin, LevelUpMenuPlugin))
+
+            // Resources
+            .insert_resource(UpgradeChoices::default())
+
+            // Events
+            .add_event::<GainExperience>()
+            .add_event::<LevelUp>()
+            .add_event::<ChestOpened>()
+            .add_event::<UpgradeChosen>()
+
+            // Systems
+            .add_systems(OnEnter(GameState::Initialize), reset_run_resource::<UpgradeChoices>)
+            .add_systems(FixedUpdate,
+                drop_experience
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::Spawn)
+            )
+            .add_systems(FixedUpdate,
+                attract_nearby_experience
+                .after(apply_health_stats)
+                .after(push_out_of_obstacles)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates)
+            )
+            .add_systems(FixedUpdate,
+                (gain_experience, open_chests, pause_for_level_up)
+                .chain()
+                .run_if(gameplay_running)
+                // last thing in the tick, so leveling up never cuts one short
+                .after(GameLoopSchedules::Despawn)
+            )
+            .add_systems(OnEnter(GameState::LevelUp), roll_upgrade_choices)
+            .add_systems(Update,
+                apply_upgrade
+                .run_if(in_state(GameState::LevelUp))
+            )
+        ;
+    }
+}
+
+fn drop_experience(
+    mut commands: Commands,
+    mut deaths: EventReader<DeathEvent>,
+    pickup_assets: Res<PickupAssets>,
+    q_drops: Query<(&Transform, &ExperienceDrop)>,
+) {
+    for event in deaths.read() {
+        let (location, drop) = if let Ok(res) = q_drops.get(event.0) {
+            res
+        } else { continue; };
+        if drop.0 == 0 { continue; }
+
+        spawn_pickup(PickupKind::Experience(drop.0), location.translation, &pickup_assets, &mut commands);
+    }
+}
+
+fn attract_nearby_experience(
+    mut commands: Commands,
+    index: Res<SpatialIndex>,
+    q_player: Query<(&Transform, &CharacterStats), With<PlayerComponent>>,
+    q_gems: Query<&Pickup, Without<Magnetized>>,
+) {
+    let (player_loc, stats) = if let Ok(res) = q_player.get_single() {
+        res
+    } else { return; };
+
+    index.0.for_each_in_radius(player_loc.translation.truncate(), stats.get(Stat::MagnetRadius), LAYER_PICKUP, |entry| {
+        if let Ok(Pickup(PickupKind::Experience(_))) = q_gems.get(entry.entity) {
+            commands.entity(entry.entity).insert(Magnetized);
+        }
+    });
+}
+
+fn gain_experience(
+    mut events: EventReader<GainExperience>,
+    mut q_player: Query<&mut PlayerExperience, (With<PlayerComponent>, Without<Dying>)>,
+    mut level_ups: EventWriter<LevelUp>,
+) {
+    let mut experience = if let Ok(xp) = q_player.get_single_mut() {
+        xp
+    } else { return; };
+
+    for event in events.read() {
+        experience.current += event.0;
+        while experience.current >= experience.to_next_level {
+            experience.current -= experience.to_next_level;
+            experience.level += 1;
+            experience.to_next_level = xp_to_level(experience.level);
+            experience.pending_level_ups += 1;
+
+            level_ups.send(LevelUp(experience.level));
+        }
+    }
+}
+
+fn open_chests(
+    mut events: EventReader<ChestOpened>,
+    mut q_player: Query<&mut PlayerExperience, (With<PlayerComponent>, Without<Dying>)>,
+) {
+    let mut experience = if let Ok(xp) = q_player.get_single_mut() {
+        xp
+    } else { return; };
+
+    for event in events.read() {
+        experience.pending_level_ups += event.0;
+    }
+}
+
+/// gameplay stays paused (in `GameState::LevelUp`) until every pending level up has an upgrade picked.
+fn pause_for_level_up(
+    mut level_ups: EventReader<LevelUp>,
+    q_player: Query<&PlayerExperience, With<PlayerComponent>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for event in level_ups.read() {
+        info!("player reached level {}", event.0);
+    }
+
+    // chests add pending upgrades without a level up, so check what's pending rather than the events.
+    if q_player.get_single().is_ok_and(|xp| xp.pending_level_ups > 0) {
+        next_state.set(GameState::LevelUp);
+    }
+}
+
+fn roll_upgrade_choices(
+    mut choices: ResMut<UpgradeChoices>,
+    mut run_rng: ResMut<RunRng>,
+    q_player: Query<&WeaponSlots, With<PlayerComponent>>,
+) {
+    let slots = if let Ok(slots) = q_player.get_single() {
+        slots
+    } else { return; };
+
+    choices.0 = random_upgrades(slots, &mut run_rng.0);
+}
+
+/// picks from the stat upgrades, plus whatever the player's `WeaponSlots` still have room for.
+fn random_upgrades(slots: &WeaponSlots, rng: &mut impl Rng) -> Vec<Upgrade> {
+    let weapon_upgrades = WeaponKind::ALL.iter().filter_map(|kind| {
+        match slots.get(*kind) {
+            Some(weapon) if weapon.is_max_level() => None,
+            Some(_) => Some(Upgrade::LevelWeapon(*kind)),
+            None if slots.is_full() => None,
+            None => Some(Upgrade::NewWeapon(*kind)),
+        }
+    });
+
+    let available: Vec<Upgrade> = Upgrade::PASSIVES.into_iter()
+        .map(Upgrade::Passive)
+        .chain(weapon_upgrades)
+        .collect();
+    available
+        .choose_multiple(rng, UPGRADE_CHOICES)
+        .copied()
+        .collect()
+}
+
+pub(crate) fn apply_upgrade(
+    mut events: EventReader<UpgradeChosen>,
+    mut choices: ResMut<UpgradeChoices>,
+    mut run_rng: ResMut<RunRng>,
+    mut q_player: Query<(&mut PlayerExperience, &mut CharacterStats, &mut WeaponSlots), With<PlayerComponent>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let (mut experience, mut stats, mut weapons) = if let Ok(res) = q_player.get_single_mut() {
+        res
+    } else { return; };
+
+    // only the first pick counts, the menu is rebuilt before another can be made
+    let upgrade = if let Some(event) = events.read().next() {
+        event.0
+    } else { return; };
+    events.clear();
+
+    info!("upgrade chosen: {:?}", upgrade);
+    match upgrade {
+        Upgrade::Passive(stat) => {
+            stats.add_modifier(StatModifier::new(stat, passive_bonus(stat), ModifierSource::Passive));
+        },
+        Upgrade::NewWeapon(kind) | Upgrade::LevelWeapon(kind) => {
+            if !weapons.add_or_level_up(kind) {
+                warn!("{:?} could not be added or leveled up", kind);
+            }
+        },
+    }
+
+    experience.pending_level_ups = experience.pending_level_ups.saturating_sub(1);
+    if experience.pending_level_ups > 0 {
+        choices.0 = random_upgrades(&weapons, &mut run_rng.0);
+    } else {
+        next_state.set(GameState::Playing);
+    }
+}
@@ -0,0 +1,188 @@
+use bevy::prelude::*;
+
+use crate::{CharacterStats, CollisionLayers, GameLoopSchedules, GameState, Velocity, WeaponKind};
+use super::stats::damage_after_armor;
+use super::types::{AnimationType, TriggerAnimation};
+use super::AnimationLengths;
+
+const DEFAULT_MAX_HEALTH: f32 = 100.0;
+const DEFAULT_INVULNERABILITY_TIME: f32 = 0.75;
+/// times per second an invulnerable `Entity` blinks out and back in
+const INVULNERABILITY_BLINK_RATE: f32 = 12.0;
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+impl Default for Health {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_HEALTH)
+    }
+}
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+/// I-frames, any damage is ignored for a short while after the `Entity` is hurt (and it blinks meanwhile).
+#[derive(Component, Debug)]
+pub struct Invulnerability(Timer);
+impl Default for Invulnerability {
+    fn default() -> Self {
+        Self::new(DEFAULT_INVULNERABILITY_TIME)
+    }
+}
+impl Invulnerability {
+    pub fn new(duration: f32) -> Self {
+        // starts out already run down, nothing has hurt it yet
+        let mut timer = Timer::from_seconds(duration, TimerMode::Once);
+        timer.tick(timer.duration());
+        Self(timer)
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.0.finished()
+    }
+}
+
+/// Added to an `Entity` once its `Health` reaches zero, it is despawned once its `Die` animation has played out.
+/// Timed in fixed ticks from the clip's length, not by the `AnimationPlayer`, so replays despawn on the same tick.
+#[derive(Component, Debug)]
+pub struct Dying(Timer);
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    /// whatever dealt the damage (a weapon, projectile, enemy etc) if there is one.
+    #[allow(dead_code)]
+    pub source: Option<Entity>,
+    /// the player's weapon behind it, if any (tallied up in `RunStats`).
+    pub weapon: Option<WeaponKind>,
+}
+
+/// The `Health` a `DamageEvent` actually took off its target, after armor and never more than it had left.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DamageTaken {
+    pub target: Entity,
+    pub amount: f32,
+    pub weapon: Option<WeaponKind>,
+}
+
+/// Sent once, the moment an `Entity`'s `Health` reaches zero.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DeathEvent(pub Entity);
+
+
+pub struct HealthPlugin;
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Events
+            .add_event::<DamageEvent>()
+            .add_event::<DamageTaken>()
+            .add_event::<DeathEvent>()
+
+            // Systems
+            .add_systems(FixedUpdate,
+                blink_invulnerable
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates)
+            )
+            .add_systems(FixedUpdate,
+                // despawning first, so anything that just died lasts a tick for `DeathEvent` readers
+                (despawn_dead, apply_damage)
+                .chain()
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::Despawn)
+            )
+        ;
+    }
+}
+
+fn apply_damage(
+    mut commands: Commands,
+    mut events: EventReader<DamageEvent>,
+    mut q_health: Query<
+        (&mut Health, Option<&mut Velocity>, Option<&CharacterStats>, Option<&mut Invulnerability>),
+        Without<Dying>,
+    >,
+
+    mut taken: EventWriter<DamageTaken>,
+    mut deaths: EventWriter<DeathEvent>,
+    mut animations: EventWriter<TriggerAnimation>,
+    animation_lengths: AnimationLengths,
+) {
+    for event in events.read() {
+        let (mut health, velocity, stats, invulnerability) = if let Ok(res) = q_health.get_mut(event.target) {
+            res
+        } else { continue; };
+        // already dead, but the `Dying` marker hasn't been applied yet.
+        if health.is_dead() { continue; }
+        if invulnerability.as_ref().is_some_and(|i| i.is_active()) { continue; }
+
+        let amount = damage_after_armor(event.amount, stats).min(health.current);
+        health.current -= amount;
+        taken.send(DamageTaken { target: event.target, amount, weapon: event.weapon });
+
+        if !health.is_dead() {
+            if let Some(mut invulnerability) = invulnerability {
+                invulnerability.0.reset();
+                animations.send(TriggerAnimation(event.target, AnimationType::TakeHit));
+            }
+            continue;
+        }
+
+        info!("entity {:?} died", event.target);
+        if let Some(mut velocity) = velocity {
+            velocity.0 = Vec3::ZERO;
+        }
+
+        // dead things no longer collide with anything
+        commands.entity(event.target).insert((
+            // with no `Die` animation, it's gone on the next tick
+            Dying(Timer::from_seconds(animation_lengths.get(event.target, AnimationType::Die).unwrap_or(0.0), TimerMode::Once)),
+            CollisionLayers::default(),
+        ));
+
+        animations.send(TriggerAnimation(event.target, AnimationType::Die));
+        deaths.send(DeathEvent(event.target));
+    }
+}
+
+fn blink_invulnerable(
+    time: Res<Time>,
+    mut q_invulnerable: Query<(&mut Invulnerability, &mut Visibility)>,
+) {
+    for (mut invulnerability, mut visibility) in q_invulnerable.iter_mut() {
+        let next = if invulnerability.0.tick(time.delta()).finished() {
+            Visibility::Inherited
+        } else if ((invulnerability.0.elapsed_secs() * INVULNERABILITY_BLINK_RATE) as u32).is_multiple_of(2) {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+
+        if *visibility != next {
+            *visibility = next;
+        }
+    }
+}
+
+fn despawn_dead(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_dying: Query<(Entity, &mut Dying)>,
+) {
+    for (entity, mut dying) in q_dying.iter_mut() {
+        if dying.0.tick(time.delta()).just_finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,405 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    fs,
+    path::PathBuf,
+};
+
+use bevy::{
+    input::{
+        gamepad::{GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, Gamepads},
+        InputSystem,
+    },
+    prelude::*,
+    utils::hashbrown::HashSet,
+};
+use serde::{Deserialize, Serialize};
+
+/// stick input under this (0..1) is ignored
+const DEFAULT_STICK_DEADZONE: f32 = 0.2;
+/// under the user's config directory
+const BINDINGS_FILE: &str = "horde_survivor/input.ron";
+
+
+/// Everything the game reacts to, systems read these (through `ActionState`) instead of raw keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Pause,
+    Confirm,
+    Cancel,
+    Dash,
+}
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::MoveUp, Action::MoveDown, Action::MoveLeft, Action::MoveRight,
+        Action::Pause, Action::Confirm, Action::Cancel, Action::Dash,
+    ];
+    /// the actions players can rebind (from the options), only their keyboard keys can be changed.
+    pub const REBINDABLE: [Action; 5] = [Action::MoveUp, Action::MoveDown, Action::MoveLeft, Action::MoveRight, Action::Pause];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move Up",
+            Action::MoveDown => "Move Down",
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::Pause => "Pause",
+            Action::Confirm => "Confirm",
+            Action::Cancel => "Cancel",
+            Action::Dash => "Dash",
+        }
+    }
+}
+
+/// One physical input that triggers an `Action`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputBinding {
+    Key(KeyCode),
+    /// on any connected gamepad
+    Gamepad(GamepadButtonType),
+}
+
+/// Which inputs trigger which `Action`s. The left stick always moves, on top of whatever is bound here.
+/// Saved to (and loaded from) `BINDINGS_FILE`, so rebinding sticks between launches.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputBindings {
+    pub bindings: BTreeMap<Action, Vec<InputBinding>>,
+    pub stick_deadzone: f32,
+}
+impl Default for InputBindings {
+    fn default() -> Self {
+        use InputBinding::{Gamepad, Key};
+
+        Self {
+            bindings: BTreeMap::from([
+                (Action::MoveUp, vec![Key(KeyCode::KeyW), Key(KeyCode::ArrowUp), Gamepad(GamepadButtonType::DPadUp)]),
+                (Action::MoveDown, vec![Key(KeyCode::KeyS), Key(KeyCode::ArrowDown), Gamepad(GamepadButtonType::DPadDown)]),
+                (Action::MoveLeft, vec![Key(KeyCode::KeyA), Key(KeyCode::ArrowLeft), Gamepad(GamepadButtonType::DPadLeft)]),
+                (Action::MoveRight, vec![Key(KeyCode::KeyD), Key(KeyCode::ArrowRight), Gamepad(GamepadButtonType::DPadRight)]),
+                (Action::Pause, vec![Key(KeyCode::Escape), Gamepad(GamepadButtonType::Start)]),
+                (Action::Confirm, vec![Key(KeyCode::Enter), Key(KeyCode::Space), Gamepad(GamepadButtonType::South)]),
+                (Action::Cancel, vec![Key(KeyCode::Escape), Key(KeyCode::Backspace), Gamepad(GamepadButtonType::East)]),
+                (Action::Dash, vec![Key(KeyCode::ShiftLeft), Gamepad(GamepadButtonType::RightTrigger)]),
+            ]),
+            stick_deadzone: DEFAULT_STICK_DEADZONE,
+        }
+    }
+}
+impl InputBindings {
+    /// The keys bound to `action`, the first is the one that gets rebound.
+    pub fn keys(&self, action: Action) -> impl Iterator<Item = KeyCode> + '_ {
+        self.bindings.get(&action)
+            .into_iter()
+            .flatten()
+            .filter_map(|binding| match binding {
+                InputBinding::Key(key) => Some(*key),
+                InputBinding::Gamepad(_) => None,
+            })
+    }
+
+    /// The action that isn't rebindable (ie. `Confirm`) already using `key`, if any.
+    pub fn reserved_by(&self, key: KeyCode) -> Option<Action> {
+        Action::ALL.into_iter()
+            .filter(|action| !Action::REBINDABLE.contains(action))
+            .find(|action| self.keys(*action).any(|bound| bound == key))
+    }
+
+    /// Makes `key` the main key for `action`.
+    /// If another rebindable action already uses `key`, it gets the key `action` had instead (so nothing is left unbound).
+    /// Keys `reserved_by` the other actions can't be taken, that one is returned instead.
+    pub fn rebind(&mut self, action: Action, key: KeyCode) -> Result<(), Action> {
+        if let Some(reserved) = self.reserved_by(key) {
+            return Err(reserved);
+        }
+        let new = InputBinding::Key(key);
+
+        let bound = self.bindings.entry(action).or_default();
+        bound.retain(|binding| *binding != new);
+        let replaced = match bound.iter().position(|binding| matches!(binding, InputBinding::Key(_))) {
+            Some(n) => Some(std::mem::replace(&mut bound[n], new)),
+            None => {
+                bound.insert(0, new);
+                None
+            },
+        };
+        // `Pause` shares its default key with `Cancel`, that's not passed on to anyone else
+        let replaced = replaced.filter(|binding| match binding {
+            InputBinding::Key(key) => self.reserved_by(*key).is_none(),
+            InputBinding::Gamepad(_) => true,
+        });
+
+        for other in Action::REBINDABLE.into_iter().filter(|other| *other != action) {
+            let bound = if let Some(bound) = self.bindings.get_mut(&other) {
+                bound
+            } else { continue; };
+            let n = if let Some(n) = bound.iter().position(|binding| *binding == new) {
+                n
+            } else { continue; };
+
+            match replaced {
+                Some(swapped) if !bound.contains(&swapped) => bound[n] = swapped,
+                _ => { bound.remove(n); },
+            }
+        }
+        Ok(())
+    }
+
+    /// Anything missing from the file (ie. an `Action` added since it was saved) keeps its default bindings.
+    fn load() -> Self {
+        let mut loaded = Self::default();
+
+        let path = if let Some(path) = bindings_path() {
+            path
+        } else { return loaded; };
+        let text = if let Ok(text) = fs::read_to_string(&path) {
+            text
+        } else { return loaded; };
+
+        match ron::from_str::<InputBindings>(&text) {
+            Ok(saved) => {
+                info!("loaded input bindings from {:?}", path);
+                loaded.bindings.extend(saved.bindings);
+                loaded.stick_deadzone = saved.stick_deadzone.clamp(0.0, 0.95);
+            },
+            Err(e) => warn!("couldn't read input bindings from {:?}, using the defaults: {}", path, e),
+        }
+        loaded
+    }
+
+    pub fn save(&self) {
+        let path = if let Some(path) = bindings_path() {
+            path
+        } else {
+            warn!("no config directory, input bindings aren't saved");
+            return;
+        };
+
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                fs::write(&path, text).map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(()) => info!("saved input bindings to {:?}", path),
+            Err(e) => warn!("couldn't save input bindings to {:?}: {}", path, e),
+        }
+    }
+}
+
+/// The platform's usual place for per-user config files.
+fn bindings_path() -> Option<PathBuf> {
+    let home = || env::var_os("HOME").map(PathBuf::from);
+    let dir = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|h| h.join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME").map(PathBuf::from).or_else(|| home().map(|h| h.join(".config")))
+    };
+    dir.map(|d| d.join(BINDINGS_FILE))
+}
+
+/// The `Action` waiting for a new key, set from the options. Nothing else sees any input until it gets one,
+/// or `Cancel` backs out of it.
+#[derive(Resource, Debug, Default)]
+pub struct PendingRebind(pub Option<Action>);
+
+/// This frame's `Action`s, updated in `PreUpdate` once bevy has read the raw input.
+#[derive(Resource, Debug, Default)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    move_axis: Vec2,
+}
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /// XY movement, no longer than 1. Combines the move actions and the left stick.
+    pub fn move_axis(&self) -> Vec2 {
+        self.move_axis
+    }
+
+    pub fn tick_input(&self) -> TickInput {
+        let quantize = |v: f32| (v * i8::MAX as f32).round() as i8;
+        let pressed = Action::ALL.iter()
+            .enumerate()
+            .filter(|(_, action)| self.pressed(**action))
+            .fold(0, |bits, (n, _)| bits | 1 << n);
+
+        TickInput {
+            move_x: quantize(self.move_axis.x),
+            move_y: quantize(self.move_axis.y),
+            pressed,
+        }
+    }
+
+    /// Replaces what's held with `input`, anything only `just_pressed` is left as it was (the menus read those).
+    pub fn apply_tick_input(&mut self, input: TickInput) {
+        self.pressed = Action::ALL.iter()
+            .enumerate()
+            .filter(|(n, _)| input.pressed & (1 << n) != 0)
+            .map(|(_, action)| *action)
+            .collect();
+        self.move_axis = input.axis();
+    }
+}
+
+/// The gameplay side of an `ActionState` for one fixed tick, quantized so it can be recorded and replayed exactly.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickInput {
+    /// `move_axis`, scaled to -127..=127
+    pub move_x: i8,
+    pub move_y: i8,
+    /// bit `n` is set if `Action::ALL[n]` is held
+    pub pressed: u8,
+}
+impl TickInput {
+    fn axis(&self) -> Vec2 {
+        (Vec2::new(self.move_x as f32, self.move_y as f32) / i8::MAX as f32).clamp_length_max(1.0)
+    }
+}
+
+pub struct ActionInputPlugin;
+impl Plugin for ActionInputPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Resources
+            .insert_resource(InputBindings::load())
+            .insert_resource(ActionState::default())
+            .insert_resource(PendingRebind::default())
+
+            // Systems
+            .add_systems(PreUpdate, update_action_state.after(InputSystem))
+        ;
+    }
+}
+
+fn update_action_state(
+    mut bindings: ResMut<InputBindings>,
+    mut pending: ResMut<PendingRebind>,
+    mut state: ResMut<ActionState>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+) {
+    let held = |binding: &InputBinding| match *binding {
+        InputBinding::Key(key) => keys.pressed(key),
+        InputBinding::Gamepad(button) => gamepads.iter().any(|g| buttons.pressed(GamepadButton::new(g, button))),
+    };
+    let tapped = |binding: &InputBinding| match *binding {
+        InputBinding::Key(key) => keys.just_pressed(key),
+        InputBinding::Gamepad(button) => gamepads.iter().any(|g| buttons.just_pressed(GamepadButton::new(g, button))),
+    };
+
+    if let Some(action) = pending.0 {
+        // the key is swallowed, it shouldn't also navigate the menu it was picked from
+        *state = ActionState::default();
+        if bindings.bindings.get(&Action::Cancel).into_iter().flatten().any(tapped) {
+            pending.0 = None;
+            return;
+        }
+        if let Some(key) = keys.get_just_pressed().next() {
+            match bindings.rebind(action, *key) {
+                Ok(()) => {
+                    bindings.save();
+                    pending.0 = None;
+                },
+                // still waiting, for a key that's free
+                Err(reserved) => info!("{:?} is kept for {}, can't bind it to {}", key, reserved.name(), action.name()),
+            }
+        }
+        return;
+    }
+
+    state.pressed.clear();
+    state.just_pressed.clear();
+    for (action, bound) in bindings.bindings.iter() {
+        if bound.iter().any(held) {
+            state.pressed.insert(*action);
+        }
+        if bound.iter().any(tapped) {
+            state.just_pressed.insert(*action);
+        }
+    }
+
+    let mut axis = Vec2::ZERO;
+    if state.pressed(Action::MoveUp) { axis += Vec2::Y; }
+    if state.pressed(Action::MoveDown) { axis -= Vec2::Y; }
+    if state.pressed(Action::MoveLeft) { axis -= Vec2::X; }
+    if state.pressed(Action::MoveRight) { axis += Vec2::X; }
+
+    // whichever stick is pushed furthest
+    let stick = gamepads.iter()
+        .map(|g| Vec2::new(
+            axes.get(GamepadAxis::new(g, GamepadAxisType::LeftStickX)).unwrap_or(0.0),
+            axes.get(GamepadAxis::new(g, GamepadAxisType::LeftStickY)).unwrap_or(0.0),
+        ))
+        .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+        .unwrap_or(Vec2::ZERO);
+    axis += apply_deadzone(stick, bindings.stick_deadzone);
+
+    state.move_axis = axis.clamp_length_max(1.0);
+}
+
+/// A radial deadzone, rescaled so movement still ramps up smoothly from the edge of it.
+fn apply_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= deadzone || deadzone >= 1.0 { return Vec2::ZERO; }
+
+    let scaled = ((length - deadzone) / (1.0 - deadzone)).min(1.0);
+    stick * (scaled / length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(bindings: &InputBindings, action: Action) -> Vec<KeyCode> {
+        bindings.keys(action).collect()
+    }
+
+    #[test]
+    fn rebind_swaps_with_another_rebindable_action() {
+        let mut bindings = InputBindings::default();
+        assert_eq!(bindings.rebind(Action::MoveUp, KeyCode::KeyS), Ok(()));
+
+        assert_eq!(keys(&bindings, Action::MoveUp), vec![KeyCode::KeyS, KeyCode::ArrowUp]);
+        assert_eq!(keys(&bindings, Action::MoveDown), vec![KeyCode::KeyW, KeyCode::ArrowDown]);
+    }
+
+    #[test]
+    fn rebind_refuses_reserved_keys() {
+        let mut bindings = InputBindings::default();
+        assert_eq!(bindings.rebind(Action::MoveUp, KeyCode::Space), Err(Action::Confirm));
+        assert_eq!(bindings.rebind(Action::Pause, KeyCode::ShiftLeft), Err(Action::Dash));
+
+        assert_eq!(keys(&bindings, Action::MoveUp), vec![KeyCode::KeyW, KeyCode::ArrowUp]);
+        assert_eq!(keys(&bindings, Action::Pause), vec![KeyCode::Escape]);
+    }
+
+    #[test]
+    fn rebind_doesnt_pass_on_a_reserved_key() {
+        let mut bindings = InputBindings::default();
+        // `Pause` gives up `Escape`, which `Cancel` still has
+        assert_eq!(bindings.rebind(Action::Pause, KeyCode::KeyW), Ok(()));
+
+        assert_eq!(keys(&bindings, Action::Pause), vec![KeyCode::KeyW]);
+        assert_eq!(keys(&bindings, Action::MoveUp), vec![KeyCode::ArrowUp]);
+        assert_eq!(bindings.reserved_by(KeyCode::Escape), Some(Action::Cancel));
+    }
+}
@@ -5,16 +5,34 @@ mod schedule;
 mod state;
 mod lighting;
 mod camera;
+mod input;
+mod rng;
 
 mod assets;
 mod ui;
 
 mod bundles;
 mod movement;
+mod collision;
+mod spatial;
+mod pathfinding;
+mod health;
+mod stats;
+mod status;
+mod projectile;
+mod weapon;
+mod pickup;
+mod destructible;
+mod obstacle;
+mod experience;
+mod run_stats;
+mod replay;
 
 mod player;
 mod enemy;
 
+mod plugins;
+
 
 pub(super) use self::{
     assets::*,
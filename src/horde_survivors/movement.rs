@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::{GameLoopSchedules, GameState};
+use crate::{GameLoopSchedules, GameState, StatusEffects};
 
 const MOVEMENT_ROTATION_SPEED:f32 = 5.0;
 
@@ -15,12 +15,14 @@ pub struct MovementPlugin;
 impl Plugin for MovementPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(Update, 
+            .add_systems(FixedUpdate, 
                 (
                     update_velocity, 
+                    apply_status_effects,
                     update_position,
                     update_facing,
                 )
+                .chain()
                 .run_if(in_state(GameState::Playing))
                 .in_set(GameLoopSchedules::EntityUpdates)
             )
@@ -28,7 +30,7 @@ impl Plugin for MovementPlugin {
     }
 }
 
-fn update_velocity(
+pub fn update_velocity(
     time: Res<Time>,
     mut query: Query<(&Acceleration, &mut Velocity)>, 
 ) {
@@ -37,7 +39,18 @@ fn update_velocity(
     }
 }
 
-fn update_position(
+/// Slows, stops or knocks back `Velocity` as the entity's status effects say, so everything after
+/// this (facing, steering, animations) sees how fast it's actually going.
+/// Whatever wants the entity moving sets its `Velocity` again every tick, so this never compounds.
+fn apply_status_effects(
+    mut query: Query<(&mut Velocity, &StatusEffects)>,
+) {
+    for (mut velocity, effects) in query.iter_mut() {
+        velocity.0 = effects.modify_velocity(velocity.0);
+    }
+}
+
+pub fn update_position(
     time: Res<Time>,
     mut query: Query<(&Velocity, &mut Transform)>, 
 ) {
@@ -48,13 +61,15 @@ fn update_position(
 
 fn update_facing(
     time: Res<Time>,
-    mut query: Query<(&Velocity, &mut Transform)>,
+    mut query: Query<(&Velocity, Option<&StatusEffects>, &mut Transform)>,
 ) {
-    for (velocity, mut transform) in query.iter_mut() {
-        let dist: f32 = velocity.0.length_squared();
+    for (velocity, effects, mut transform) in query.iter_mut() {
+        // knocked back things keep facing the way they were going, stopped ones don't turn at all
+        let velocity = velocity.0 - effects.map_or(Vec3::ZERO, |e| e.knockback().extend(0.0));
+        let dist: f32 = velocity.length_squared();
         if  -0.05 < dist && dist < 0.05 { continue; }
 
-        let target_angle = (f64::atan2(velocity.0.x as f64, velocity.0.y as f64) * -1.0) as f32;
+        let target_angle = -f64::atan2(velocity.x as f64, velocity.y as f64) as f32;
         let target = Quat::from_rotation_z(target_angle);
 
         transform.rotation = transform.rotation.lerp(target, time.delta_seconds() * MOVEMENT_ROTATION_SPEED);
@@ -0,0 +1,169 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+use crate::{
+    contact, update_position, Collider, CollisionLayers, Dying, GameLoopSchedules, GameState, Obstacle, RunScoped,
+    SpatialIndex, StaticObjectBundle, Velocity,
+    LAYER_DESTRUCTIBLE, LAYER_ENEMY, LAYER_PLAYER, LAYER_STATIC,
+};
+
+const PILLAR_RADIUS: f32 = 0.6;
+const PILLAR_HEIGHT: f32 = 3.0;
+const PILLARS: &[Vec2] = &[
+    Vec2::new(6.0, 6.0),
+    Vec2::new(-6.0, 6.0),
+    Vec2::new(6.0, -6.0),
+    Vec2::new(-6.0, -6.0),
+    Vec2::new(30.0, 6.0),
+    Vec2::new(30.0, -6.0),
+    Vec2::new(-30.0, 6.0),
+    Vec2::new(-30.0, -6.0),
+];
+
+const GRAVESTONE_HALF_LENGTH: f32 = 0.35;
+const GRAVESTONE_RADIUS: f32 = 0.2;
+const GRAVESTONE_HEIGHT: f32 = 0.9;
+const GRAVESTONE_SPACING: Vec2 = Vec2::new(3.0, 3.0);
+/// gravestones are laid out in rows, starting from each graveyard's corner
+const GRAVEYARDS: &[Vec2] = &[
+    Vec2::new(-21.0, 20.0),
+    Vec2::new(15.0, -26.0),
+];
+const GRAVEYARD_ROWS: i32 = 3;
+const GRAVEYARD_COLUMNS: i32 = 3;
+
+const WALL_HALF_LENGTH: f32 = 3.0;
+const WALL_RADIUS: f32 = 0.35;
+const WALL_HEIGHT: f32 = 2.0;
+/// where each wall is centered, and its angle (radians, from running along Y)
+const WALLS: &[(Vec2, f32)] = &[
+    (Vec2::new(0.0, 18.0), FRAC_PI_2),
+    (Vec2::new(0.0, -18.0), FRAC_PI_2),
+    (Vec2::new(18.0, 0.0), 0.0),
+    (Vec2::new(-18.0, 0.0), 0.0),
+];
+
+const STONE_COLOR: Color = Color::rgb(0.45, 0.45, 0.5);
+
+
+/// Static geometry (pillars, gravestones and walls) that nothing can walk through.
+#[derive(Component, Debug, Default)]
+pub struct Scenery;
+
+pub struct ObstaclePlugin;
+impl Plugin for ObstaclePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnExit(GameState::Initialize), spawn_obstacles)
+            .add_systems(FixedUpdate,
+                push_out_of_obstacles
+                .after(update_position)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates)
+            )
+        ;
+    }
+}
+
+fn spawn_obstacles(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(STONE_COLOR);
+
+    // meshes are built along Y (like capsule colliders), standing up from the ground
+    let pillar_mesh = meshes.add(Cylinder::new(PILLAR_RADIUS, PILLAR_HEIGHT));
+    let pillar_mesh_transform = Transform::from_xyz(0.0, 0.0, PILLAR_HEIGHT * 0.5)
+        .with_rotation(Quat::from_rotation_x(FRAC_PI_2));
+    for location in PILLARS {
+        spawn_obstacle(
+            Transform::from_translation(location.extend(0.0)),
+            Collider::circle(PILLAR_RADIUS),
+            (pillar_mesh.clone(), material.clone(), pillar_mesh_transform),
+            &mut commands,
+        );
+    }
+
+    let gravestone_length = (GRAVESTONE_HALF_LENGTH + GRAVESTONE_RADIUS) * 2.0;
+    let gravestone_mesh = meshes.add(Cuboid::new(GRAVESTONE_RADIUS * 2.0, gravestone_length, GRAVESTONE_HEIGHT));
+    let gravestone_mesh_transform = Transform::from_xyz(0.0, 0.0, GRAVESTONE_HEIGHT * 0.5);
+    for corner in GRAVEYARDS {
+        for row in 0..GRAVEYARD_ROWS {
+            for column in 0..GRAVEYARD_COLUMNS {
+                let location = *corner + Vec2::new(column as f32, row as f32) * GRAVESTONE_SPACING;
+                spawn_obstacle(
+                    Transform::from_translation(location.extend(0.0)).with_rotation(Quat::from_rotation_z(FRAC_PI_2)),
+                    Collider::capsule(GRAVESTONE_HALF_LENGTH, GRAVESTONE_RADIUS),
+                    (gravestone_mesh.clone(), material.clone(), gravestone_mesh_transform),
+                    &mut commands,
+                );
+            }
+        }
+    }
+
+    let wall_length = (WALL_HALF_LENGTH + WALL_RADIUS) * 2.0;
+    let wall_mesh = meshes.add(Cuboid::new(WALL_RADIUS * 2.0, wall_length, WALL_HEIGHT));
+    let wall_mesh_transform = Transform::from_xyz(0.0, 0.0, WALL_HEIGHT * 0.5);
+    for (location, angle) in WALLS {
+        spawn_obstacle(
+            Transform::from_translation(location.extend(0.0)).with_rotation(Quat::from_rotation_z(*angle)),
+            Collider::capsule(WALL_HALF_LENGTH, WALL_RADIUS),
+            (wall_mesh.clone(), material.clone(), wall_mesh_transform),
+            &mut commands,
+        );
+    }
+}
+
+fn spawn_obstacle(
+    transform: Transform,
+    collider: Collider,
+    (mesh, material, mesh_transform): (Handle<Mesh>, Handle<StandardMaterial>, Transform),
+    commands: &mut Commands,
+) -> Entity {
+    commands.spawn((
+        StaticObjectBundle {
+            transform: SpatialBundle {
+                transform,
+                ..default()
+            },
+            collider,
+            layers: CollisionLayers::new(LAYER_STATIC, LAYER_PLAYER | LAYER_ENEMY),
+        },
+        Scenery,
+        Obstacle,
+        RunScoped,
+    )).with_children(|parent| {
+        parent.spawn(PbrBundle {
+            mesh,
+            material,
+            transform: mesh_transform,
+            ..default()
+        });
+    }).id()
+}
+
+/// Anything that moved into an `Obstacle` (scenery, or a torch that's still standing) this frame is pushed
+/// back out along the contact normal, so it ends up sliding along the surface instead of passing through.
+pub fn push_out_of_obstacles(
+    index: Res<SpatialIndex>,
+    q_static: Query<(&Transform, &Collider, &CollisionLayers), (With<Obstacle>, Without<Velocity>, Without<Dying>)>,
+    mut q_movables: Query<(&mut Transform, &Collider, &CollisionLayers), With<Velocity>>,
+) {
+    // statics never move, so last frame's index still has them in the right place
+    let max_radius = index.0.max_radius();
+    for (mut transform, collider, layers) in q_movables.iter_mut() {
+        let reach = collider.bounding_radius() + max_radius;
+        index.0.for_each_in_radius(transform.translation.truncate(), reach, LAYER_STATIC | LAYER_DESTRUCTIBLE, |entry| {
+            let Ok((s_t, s_col, s_layers)) = q_static.get(entry.entity) else { return; };
+            // only what the obstacle blocks, a projectile that hits one is dealt with by `projectile_hits`
+            if s_layers.filters & layers.memberships == 0 { return; }
+
+            // `normal` points from the movable into the obstacle
+            if let Some(hit) = contact(&transform, collider, s_t, s_col) {
+                transform.translation -= (hit.normal * hit.depth).extend(0.0);
+            }
+        });
+    }
+}
@@ -0,0 +1,293 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use bevy::math::{IVec2, Vec2};
+
+// integer step costs, so the search doesn't have to order floats
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+const NEIGHBOURS: [(IVec2, u32); 8] = [
+    (IVec2::new(1, 0), STRAIGHT_COST),
+    (IVec2::new(-1, 0), STRAIGHT_COST),
+    (IVec2::new(0, 1), STRAIGHT_COST),
+    (IVec2::new(0, -1), STRAIGHT_COST),
+    (IVec2::new(1, 1), DIAGONAL_COST),
+    (IVec2::new(1, -1), DIAGONAL_COST),
+    (IVec2::new(-1, 1), DIAGONAL_COST),
+    (IVec2::new(-1, -1), DIAGONAL_COST),
+];
+
+/// A square grid of cells (centered wherever it was last `reset`) where every cell points along the
+/// cheapest path towards a single goal, around any blocked cells.
+///
+/// Any number of agents can then be steered with a single lookup each (`direction_at`).
+#[derive(Debug, Clone)]
+pub struct FlowField {
+    cell_size: f32,
+    /// cells from the center to each edge
+    half_extent: i32,
+    /// cell coordinate (world space) of the grid's min corner
+    min_cell: IVec2,
+    blocked: Vec<bool>,
+    /// cost to reach the goal from each cell, `u32::MAX` if it can't be reached
+    cost: Vec<u32>,
+    flow: Vec<Vec2>,
+}
+
+impl FlowField {
+    pub fn new(cell_size: f32, half_extent: i32) -> Self {
+        let width = (half_extent * 2 + 1) as usize;
+        Self {
+            cell_size,
+            half_extent,
+            min_cell: IVec2::splat(-half_extent),
+            blocked: vec![false; width * width],
+            cost: vec![u32::MAX; width * width],
+            flow: vec![Vec2::ZERO; width * width],
+        }
+    }
+
+    /// cells along each side
+    pub fn width(&self) -> i32 {
+        self.half_extent * 2 + 1
+    }
+
+    pub fn cell_of(&self, point: Vec2) -> IVec2 {
+        (point / self.cell_size).floor().as_ivec2()
+    }
+
+    fn index_of(&self, cell: IVec2) -> Option<usize> {
+        let local = cell - self.min_cell;
+        let width = self.width();
+        if local.x < 0 || local.y < 0 || local.x >= width || local.y >= width { return None; }
+        Some((local.y * width + local.x) as usize)
+    }
+
+    /// Moves the grid to be centered on `center` and clears everything, ready to block cells again.
+    pub fn reset(&mut self, center: Vec2) {
+        self.min_cell = self.cell_of(center) - IVec2::splat(self.half_extent);
+        self.blocked.fill(false);
+        self.cost.fill(u32::MAX);
+        self.flow.fill(Vec2::ZERO);
+    }
+
+    pub fn block_cell(&mut self, cell: IVec2) {
+        if let Some(i) = self.index_of(cell) {
+            self.blocked[i] = true;
+        }
+    }
+
+    /// Blocks every cell the circle overlaps.
+    pub fn block_circle(&mut self, center: Vec2, radius: f32) {
+        let min = self.cell_of(center - Vec2::splat(radius));
+        let max = self.cell_of(center + Vec2::splat(radius));
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let cell = IVec2::new(x, y);
+                let cell_min = cell.as_vec2() * self.cell_size;
+                let closest = center.clamp(cell_min, cell_min + Vec2::splat(self.cell_size));
+                if closest.distance_squared(center) < radius * radius {
+                    self.block_cell(cell);
+                }
+            }
+        }
+    }
+
+    /// Blocks every cell a capsule (the segment `a` to `b` grown by `radius`) overlaps.
+    pub fn block_capsule(&mut self, a: Vec2, b: Vec2, radius: f32) {
+        // a run of circles, close enough together that no cell between them gets missed
+        let steps = (a.distance(b) / (self.cell_size * 0.5)).ceil().max(1.0) as usize;
+        for n in 0..=steps {
+            self.block_circle(a.lerp(b, n as f32 / steps as f32), radius);
+        }
+    }
+
+    pub fn is_blocked(&self, cell: IVec2) -> bool {
+        self.index_of(cell).is_none_or(|i| self.blocked[i])
+    }
+
+    /// Cost (in tenths of a cell) from `cell` to the goal, `None` if it's off the grid or unreachable.
+    pub fn cost_at(&self, cell: IVec2) -> Option<u32> {
+        self.index_of(cell)
+            .map(|i| self.cost[i])
+            .filter(|cost| *cost != u32::MAX)
+    }
+
+    /// Fills in every cell's cost and direction towards `goal` (Dijkstra out from the goal's cell).
+    /// Diagonal moves aren't allowed to cut the corner of a blocked cell.
+    pub fn compute(&mut self, goal: Vec2) {
+        self.cost.fill(u32::MAX);
+        self.flow.fill(Vec2::ZERO);
+
+        let goal_cell = self.cell_of(goal);
+        let goal_index = if let Some(i) = self.index_of(goal_cell) {
+            i
+        } else { return; };
+        // whatever is standing on the goal can obviously reach it
+        self.blocked[goal_index] = false;
+        self.cost[goal_index] = 0;
+
+        let mut open = BinaryHeap::new();
+        open.push(Reverse((0u32, goal_cell.x, goal_cell.y)));
+        while let Some(Reverse((cost, x, y))) = open.pop() {
+            let cell = IVec2::new(x, y);
+            let index = self.index_of(cell).expect("only cells on the grid are queued");
+            if cost > self.cost[index] { continue; }
+
+            for (offset, step) in NEIGHBOURS {
+                let next = cell + offset;
+                if !self.can_step(cell, offset) { continue; }
+
+                let next_index = self.index_of(next).expect("can_step checks the grid bounds");
+                let next_cost = cost + step;
+                if next_cost < self.cost[next_index] {
+                    self.cost[next_index] = next_cost;
+                    open.push(Reverse((next_cost, next.x, next.y)));
+                }
+            }
+        }
+
+        // every reachable cell points at its cheapest neighbour
+        let width = self.width();
+        for y in 0..width {
+            for x in 0..width {
+                let cell = self.min_cell + IVec2::new(x, y);
+                let index = (y * width + x) as usize;
+                if self.cost[index] == u32::MAX || self.cost[index] == 0 { continue; }
+
+                let best = NEIGHBOURS.iter()
+                    .filter(|(offset, _)| self.can_step(cell, *offset))
+                    .filter_map(|(offset, _)| self.cost_at(cell + *offset).map(|cost| (cost, *offset)))
+                    .min_by_key(|(cost, _)| *cost);
+                if let Some((_, offset)) = best {
+                    self.flow[index] = offset.as_vec2().normalize();
+                }
+            }
+        }
+    }
+
+    /// can something move from `cell` one step in `offset`, without clipping a blocked corner.
+    fn can_step(&self, cell: IVec2, offset: IVec2) -> bool {
+        if self.is_blocked(cell + offset) { return false; }
+        if offset.x != 0 && offset.y != 0 {
+            return !self.is_blocked(cell + IVec2::new(offset.x, 0))
+                && !self.is_blocked(cell + IVec2::new(0, offset.y));
+        }
+        true
+    }
+
+    /// The direction to head from `point` to follow the cheapest path to the goal.
+    /// `None` when `point` is off the grid, in the goal's cell, or can't reach the goal at all.
+    pub fn direction_at(&self, point: Vec2) -> Option<Vec2> {
+        let index = self.index_of(self.cell_of(point))?;
+        let dir = self.flow[index];
+        if dir == Vec2::ZERO { None } else { Some(dir) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a 5x5 grid of unit cells, from (-2, -2) to (2, 2)
+    fn field(walls: &[(i32, i32)]) -> FlowField {
+        let mut field = FlowField::new(1.0, 2);
+        field.reset(Vec2::ZERO);
+        for (x, y) in walls {
+            field.block_cell(IVec2::new(*x, *y));
+        }
+        field
+    }
+
+    fn center(x: i32, y: i32) -> Vec2 {
+        Vec2::new(x as f32, y as f32) + Vec2::splat(0.5)
+    }
+
+    #[test]
+    fn open_grid_heads_straight_for_the_goal() {
+        let mut field = field(&[]);
+        field.compute(center(0, 0));
+
+        assert_eq!(field.cost_at(IVec2::new(2, 0)), Some(2 * STRAIGHT_COST));
+        assert_eq!(field.cost_at(IVec2::new(-2, 2)), Some(2 * DIAGONAL_COST));
+        assert_eq!(field.direction_at(center(2, 0)), Some(Vec2::NEG_X));
+        assert_eq!(field.direction_at(center(0, -2)), Some(Vec2::Y));
+        assert_eq!(field.direction_at(center(-2, 2)), Some(Vec2::new(1.0, -1.0).normalize()));
+        // already there
+        assert_eq!(field.direction_at(center(0, 0)), None);
+    }
+
+    #[test]
+    fn off_the_grid_has_no_direction() {
+        let mut field = field(&[]);
+        field.compute(center(0, 0));
+        assert_eq!(field.direction_at(center(3, 0)), None);
+        assert_eq!(field.cost_at(IVec2::new(-3, -3)), None);
+
+        // nothing can reach a goal that isn't on the grid
+        field.compute(center(10, 0));
+        assert_eq!(field.direction_at(center(2, 0)), None);
+        assert_eq!(field.cost_at(IVec2::new(2, 0)), None);
+    }
+
+    #[test]
+    fn diagonals_dont_cut_blocked_corners() {
+        let mut field = field(&[(1, 0)]);
+        field.compute(center(0, 0));
+
+        // straight at the goal would clip the wall's corner, so it goes up and over
+        assert_eq!(field.direction_at(center(1, 1)), Some(Vec2::NEG_X));
+        assert_eq!(field.cost_at(IVec2::new(1, 1)), Some(2 * STRAIGHT_COST));
+        assert_eq!(field.direction_at(center(1, -1)), Some(Vec2::NEG_X));
+    }
+
+    #[test]
+    fn cells_next_to_walls_go_around() {
+        let mut field = field(&[(1, 0)]);
+        field.compute(center(0, 0));
+
+        // right behind the wall, it has to step sideways first
+        let dir = field.direction_at(center(2, 0)).unwrap();
+        assert_eq!(dir.x, 0.0);
+        assert_eq!(field.cost_at(IVec2::new(2, 0)), Some(4 * STRAIGHT_COST));
+        // the wall itself has nowhere to go
+        assert!(field.is_blocked(IVec2::new(1, 0)));
+        assert_eq!(field.direction_at(center(1, 0)), None);
+        assert_eq!(field.cost_at(IVec2::new(1, 0)), None);
+    }
+
+    #[test]
+    fn walls_with_a_gap_are_routed_through_it() {
+        // a wall down x = 1, open only at the top
+        let mut field = field(&[(1, -2), (1, -1), (1, 0), (1, 1)]);
+        field.compute(center(0, 0));
+
+        assert_eq!(field.direction_at(center(2, -2)), Some(Vec2::Y));
+        assert_eq!(field.direction_at(center(2, 1)), Some(Vec2::Y));
+        // through the gap, and clear of the wall's end before turning back down
+        assert_eq!(field.direction_at(center(1, 2)), Some(Vec2::NEG_X));
+        assert_eq!(field.direction_at(center(0, 2)), Some(Vec2::NEG_Y));
+        assert_eq!(field.cost_at(IVec2::new(2, -2)), Some(8 * STRAIGHT_COST));
+    }
+
+    #[test]
+    fn walled_off_cells_are_unreachable() {
+        let mut field = field(&[(1, -2), (1, -1), (1, 0), (1, 1), (1, 2)]);
+        field.compute(center(0, 0));
+
+        for y in -2..=2 {
+            assert_eq!(field.cost_at(IVec2::new(2, y)), None);
+            assert_eq!(field.direction_at(center(2, y)), None);
+        }
+        assert_eq!(field.direction_at(center(-2, 0)), Some(Vec2::X));
+    }
+
+    #[test]
+    fn the_goal_is_never_blocked() {
+        let mut field = field(&[(0, 0)]);
+        field.compute(center(0, 0));
+
+        assert_eq!(field.cost_at(IVec2::new(0, 0)), Some(0));
+        assert_eq!(field.direction_at(center(1, 0)), Some(Vec2::NEG_X));
+    }
+}
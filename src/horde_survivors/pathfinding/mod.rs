@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+
+use crate::{reset_run_resource, Collider, Dying, GameLoopSchedules, GameState, PlayerComponent};
+
+mod flow_field;
+pub use flow_field::*;
+
+const FLOW_CELL_SIZE: f32 = 1.0;
+/// cells out from the player in each direction, past this enemies just head straight for the player
+const FLOW_HALF_EXTENT: i32 = 30;
+/// seconds between recomputing the field
+const FLOW_UPDATE_INTERVAL: f32 = 0.25;
+/// obstacles are grown by this much, so enemies don't path right along their edges
+const OBSTACLE_CLEARANCE: f32 = 0.4;
+
+/// Something enemies should path around, rather than walk into.
+#[derive(Component, Debug, Default)]
+pub struct Obstacle;
+
+/// Flow field leading to the player, recomputed every `FLOW_UPDATE_INTERVAL` seconds.
+#[derive(Resource, Debug)]
+pub struct PlayerFlowField(pub FlowField);
+impl Default for PlayerFlowField {
+    fn default() -> Self {
+        Self(FlowField::new(FLOW_CELL_SIZE, FLOW_HALF_EXTENT))
+    }
+}
+
+#[derive(Resource, Debug)]
+struct FlowFieldTimer(Timer);
+impl Default for FlowFieldTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(FLOW_UPDATE_INTERVAL, TimerMode::Repeating))
+    }
+}
+
+pub struct PathfindingPlugin;
+impl Plugin for PathfindingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Resources
+            .insert_resource(PlayerFlowField::default())
+            .insert_resource(FlowFieldTimer::default())
+
+            // Systems
+            .add_systems(OnEnter(GameState::Initialize), (
+                reset_run_resource::<PlayerFlowField>,
+                reset_run_resource::<FlowFieldTimer>,
+            ))
+            .add_systems(FixedUpdate,
+                update_flow_field
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::PostSpawn)
+            )
+        ;
+    }
+}
+
+fn update_flow_field(
+    time: Res<Time>,
+    mut timer: ResMut<FlowFieldTimer>,
+    mut field: ResMut<PlayerFlowField>,
+    q_player: Query<&Transform, With<PlayerComponent>>,
+    q_obstacles: Query<(&Transform, &Collider), (With<Obstacle>, Without<Dying>)>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() { return; }
+
+    let goal = if let Ok(t) = q_player.get_single() {
+        t.translation.truncate()
+    } else { return; };
+
+    field.0.reset(goal);
+    for (transform, collider) in q_obstacles.iter() {
+        let (a, b, radius) = collider.segment(transform);
+        field.0.block_capsule(a, b, radius + OBSTACLE_CLEARANCE);
+    }
+    field.0.compute(goal);
+}
@@ -0,0 +1,226 @@
+use bevy::{
+    prelude::*,
+    utils::hashbrown::HashMap,
+};
+
+use crate::{
+    ApplyStatus, ChestOpened, Collider, CollisionLayers, CollisionStarted, DamageEvent, Dying, GainExperience, GameLoopSchedules, GameState,
+    Health, MovableObjectBundle, PlayerComponent, RunScoped, SpatialIndex, StatusEffect, StatusKind, Velocity,
+    projectile_hits, update_velocity,
+    LAYER_ENEMY, LAYER_PICKUP, LAYER_PLAYER,
+};
+
+const PICKUP_COLLIDER_RADIUS: f32 = 0.35;
+const PICKUP_MESH_HEIGHT: f32 = 0.4;
+const PICKUP_SPIN_SPEED: f32 = 2.0;
+
+const MAGNET_PULL_SPEED: f32 = 15.0;
+const BOMB_RADIUS: f32 = 20.0;
+const BOMB_DAMAGE: f32 = 10_000.0;
+/// anything tough enough to survive a bomb (ie. a boss) is frozen for this long instead
+const BOMB_FREEZE_DURATION: f32 = 3.0;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PickupKind {
+    /// an experience gem worth this much
+    Experience(u32),
+    /// restores this much `Health`
+    Health(f32),
+    /// pulls every pickup on the field to the player
+    Magnet,
+    /// kills everything around the player
+    Bomb,
+    /// grants this many upgrades to pick from
+    Chest(u32),
+}
+impl PickupKind {
+    // used as the key into `PickupAssets`
+    fn asset_id(&self) -> u8 {
+        match self {
+            PickupKind::Experience(_) => 0,
+            PickupKind::Health(_) => 1,
+            PickupKind::Magnet => 2,
+            PickupKind::Bomb => 3,
+            PickupKind::Chest(_) => 4,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Pickup(pub PickupKind);
+
+/// a `Pickup` flying towards the player.
+#[derive(Component, Debug)]
+pub struct Magnetized;
+
+/// There are no models for pickups, so they are simple shapes generated at startup.
+#[derive(Resource, Default)]
+pub struct PickupAssets(HashMap<u8, (Handle<Mesh>, Handle<StandardMaterial>)>);
+
+
+pub struct PickupPlugin;
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Resources
+            .insert_resource(PickupAssets::default())
+
+            // Systems
+            .add_systems(Startup, create_pickup_assets)
+            .add_systems(FixedUpdate,
+                (pull_magnetized_pickups, spin_pickups)
+                .before(update_velocity)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates)
+            )
+            .add_systems(FixedUpdate,
+                collect_pickups
+                .after(projectile_hits)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::CollisionDetection)
+            )
+        ;
+    }
+}
+
+fn create_pickup_assets(
+    mut assets: ResMut<PickupAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut add = |kind: PickupKind, mesh: Mesh, color: Color| {
+        assets.0.insert(kind.asset_id(), (meshes.add(mesh), materials.add(StandardMaterial {
+            base_color: color,
+            emissive: color * 0.5,
+            ..default()
+        })));
+    };
+
+    add(PickupKind::Experience(0), Cuboid::new(0.2, 0.2, 0.2).into(), Color::rgb_u8(60, 230, 200));
+    add(PickupKind::Health(0.0), Cuboid::new(0.35, 0.35, 0.35).into(), Color::rgb_u8(220, 40, 60));
+    add(PickupKind::Magnet, Torus::new(0.1, 0.25).into(), Color::rgb_u8(60, 120, 255));
+    add(PickupKind::Bomb, Sphere::new(0.25).into(), Color::rgb_u8(40, 40, 40));
+    add(PickupKind::Chest(0), Cuboid::new(0.7, 0.5, 0.45).into(), Color::rgb_u8(230, 180, 40));
+}
+
+pub(crate) fn spawn_pickup(
+    kind: PickupKind,
+    location: Vec3,
+    assets: &PickupAssets,
+    commands: &mut Commands,
+) -> Entity {
+    let (mesh, material) = assets.0.get(&kind.asset_id())
+        .expect("assets are created for every pickup kind")
+        .clone();
+
+    commands.spawn((
+        MovableObjectBundle {
+            transform: SpatialBundle {
+                transform: Transform::from_translation(location),
+                ..default()
+            },
+            collider: Collider::circle(PICKUP_COLLIDER_RADIUS),
+            layers: CollisionLayers::new(LAYER_PICKUP, LAYER_PLAYER),
+            ..default()
+        },
+        Pickup(kind),
+        RunScoped,
+    )).with_children(|parent| {
+        parent.spawn(PbrBundle {
+            mesh,
+            material,
+            transform: Transform::from_xyz(0.0, 0.0, PICKUP_MESH_HEIGHT),
+            ..default()
+        });
+    }).id()
+}
+
+fn pull_magnetized_pickups(
+    q_player: Query<&Transform, With<PlayerComponent>>,
+    mut q_pickups: Query<(&Transform, &mut Velocity), (With<Magnetized>, Without<PlayerComponent>)>,
+) {
+    let player_loc = if let Ok(t) = q_player.get_single() {
+        t.translation
+    } else { return; };
+
+    for (pickup_loc, mut velocity) in q_pickups.iter_mut() {
+        let dir = (player_loc - pickup_loc.translation).truncate().normalize_or_zero();
+        velocity.0 = dir.extend(0.0) * MAGNET_PULL_SPEED;
+    }
+}
+
+fn spin_pickups(
+    time: Res<Time>,
+    q_pickups: Query<&Children, With<Pickup>>,
+    mut q_meshes: Query<&mut Transform, Without<Pickup>>,
+) {
+    let spin = Quat::from_rotation_z(PICKUP_SPIN_SPEED * time.delta_seconds());
+    for children in q_pickups.iter() {
+        for child in children.iter() {
+            if let Ok(mut mesh_transform) = q_meshes.get_mut(*child) {
+                mesh_transform.rotation = spin * mesh_transform.rotation;
+            }
+        }
+    }
+}
+
+pub fn collect_pickups(
+    mut commands: Commands,
+    mut collisions: EventReader<CollisionStarted>,
+    index: Res<SpatialIndex>,
+    q_pickups: Query<(Entity, &Pickup)>,
+    mut q_player: Query<(&Transform, &mut Health), (With<PlayerComponent>, Without<Dying>)>,
+    mut damage: EventWriter<DamageEvent>,
+    mut experience: EventWriter<GainExperience>,
+    mut chests: EventWriter<ChestOpened>,
+    mut statuses: EventWriter<ApplyStatus>,
+) {
+    for event in collisions.read() {
+        let (pickup_entity, player_entity) = if q_pickups.contains(event.0) {
+            (event.0, event.1)
+        } else if q_pickups.contains(event.1) {
+            (event.1, event.0)
+        } else { continue; };
+
+        let (player_loc, mut health) = if let Ok(res) = q_player.get_mut(player_entity) {
+            res
+        } else { continue; };
+        let (_, pickup) = q_pickups.get(pickup_entity).expect("checked above");
+
+        match pickup.0 {
+            PickupKind::Experience(amount) => {
+                experience.send(GainExperience(amount));
+            },
+            PickupKind::Health(amount) => {
+                info!("picked up {:?}", pickup.0);
+                health.current = (health.current + amount).min(health.max);
+            },
+            PickupKind::Magnet => {
+                info!("picked up {:?}", pickup.0);
+                for (entity, _) in q_pickups.iter() {
+                    if entity == pickup_entity { continue; }
+                    commands.entity(entity).insert(Magnetized);
+                }
+            },
+            PickupKind::Bomb => {
+                info!("picked up {:?}", pickup.0);
+                index.0.for_each_in_radius(player_loc.translation.truncate(), BOMB_RADIUS, LAYER_ENEMY, |entry| {
+                    damage.send(DamageEvent {
+                        target: entry.entity,
+                        amount: BOMB_DAMAGE,
+                        source: Some(pickup_entity),
+                        weapon: None,
+                    });
+                    statuses.send(ApplyStatus(entry.entity, StatusEffect::new(StatusKind::Freeze, BOMB_FREEZE_DURATION)));
+                });
+            },
+            PickupKind::Chest(upgrades) => {
+                info!("picked up {:?}", pickup.0);
+                chests.send(ChestOpened(upgrades));
+            },
+        }
+
+        commands.entity(pickup_entity).despawn_recursive();
+    }
+}
@@ -1,36 +1,77 @@
 use bevy::prelude::*;
 
 use crate::{
+    Action,
+    ActionState,
+    CharacterStats,
+    Collider,
+    CollisionLayers,
+    Dying,
     GameLoopSchedules, 
     GameState,
-    Velocity
+    Health,
+    Invulnerability,
+    PlayerExperience,
+    RunScoped,
+    Stat,
+    StatusEffects,
+    Velocity,
+    WeaponSlots,
+    LAYER_ENEMY,
+    LAYER_PICKUP,
+    LAYER_PLAYER,
 };
 use super::assets::types::*;
 use super::bundles::*;
 
+const PLAYER_COLLIDER_RADIUS: f32 = 0.4;
+/// seconds
+const DASH_DURATION: f32 = 0.2;
+const DASH_COOLDOWN: f32 = 1.5;
+/// `Stat::MoveSpeed` is multiplied by this while dashing
+const DASH_SPEED_MULT: f32 = 3.5;
+
 #[derive(Component, Debug, Default)]
 pub struct PlayerComponent;
 
+/// A short burst of speed along the way the player is moving, on `Action::Dash`.
 #[derive(Component, Debug)]
-struct PlayerModifiers {
-    move_speed: f32,
-    move_speed_mod: f32,
+pub struct Dash {
+    /// runs for as long as the dash lasts
+    active: Timer,
+    cooldown: Timer,
+    direction: Vec2,
 }
-impl Default for PlayerModifiers {
+impl Default for Dash {
     fn default() -> Self {
-        Self {
-            move_speed: 5.0,
-            move_speed_mod: 1.0,
-        }
+        // both already run out, the player can dash straight away
+        let mut active = Timer::from_seconds(DASH_DURATION, TimerMode::Once);
+        active.tick(active.duration());
+        let mut cooldown = Timer::from_seconds(DASH_COOLDOWN, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Self { active, cooldown, direction: Vec2::ZERO }
+    }
+}
+impl Dash {
+    pub fn is_dashing(&self) -> bool {
+        !self.active.finished()
     }
 }
 
 #[derive(Bundle, Default)]
 struct PlayerBundle {
     movement: MovableObjectBundle,
-    modifiers: PlayerModifiers,
+    stats: CharacterStats,
+    health: Health,
+    invulnerability: Invulnerability,
+    dash: Dash,
+    status: StatusEffects,
+    animation_speed: AnimationSpeed,
+    weapons: WeaponSlots,
+    experience: PlayerExperience,
     asset_key: AssetKey,
     marker: PlayerComponent,
+    run_scoped: RunScoped,
 }
 
 pub struct PlayerPlugin;
@@ -40,10 +81,15 @@ impl Plugin for PlayerPlugin {
                 spawn_player
                 .in_set(GameLoopSchedules::Spawn),
             )
-            .add_systems(Update, 
+            .add_systems(FixedUpdate, 
                 handle_move_ctl
+                .run_if(in_state(GameState::Playing))
                 .in_set(GameLoopSchedules::ProcessInput),
             )
+            .add_systems(Update,
+                end_run_on_player_despawn
+                .run_if(in_state(GameState::Playing))
+            )
             ;
     }
 }
@@ -55,6 +101,11 @@ pub(crate) fn spawn_player(
     info!("spawning player");
     let asset_key = AssetKey(ASSET_KEY_PLAYER.into());
     let player_entity = commands.spawn(PlayerBundle{
+        movement: MovableObjectBundle {
+            collider: Collider::circle(PLAYER_COLLIDER_RADIUS),
+            layers: CollisionLayers::new(LAYER_PLAYER, LAYER_ENEMY | LAYER_PICKUP),
+            ..default()
+        },
         asset_key: asset_key.clone(),
         ..default()
     }).id();
@@ -67,36 +118,31 @@ pub(crate) fn spawn_player(
 }
 
 fn handle_move_ctl(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    actions: Res<ActionState>,
     mut events: EventWriter<TriggerAnimation>,
-    mut query: Query<(&mut Velocity, &PlayerModifiers, Entity), With<PlayerComponent>>,
+    mut query: Query<(&mut Velocity, &mut Dash, &CharacterStats, Entity), (With<PlayerComponent>, Without<Dying>)>,
 ) {
-    let (mut velocity, modifiers, player_entity) = if let Ok(res) = query.get_single_mut() {
+    let (mut velocity, mut dash, stats, player_entity) = if let Ok(res) = query.get_single_mut() {
         res
     } else { return; };
-    
-    let mut move_dir = Vec3::ZERO;
 
-    // TODO: abstract this a bit more so we can handle multiple sets of inputs
-    //       maybe even configurable sets for keyboard / game pads etc?
-    if keyboard_input.pressed(KeyCode::KeyA) {
-        move_dir -= Vec3::X;
-    }
-    if keyboard_input.pressed(KeyCode::KeyD) {
-        move_dir += Vec3::X;
-    }
-    if keyboard_input.pressed(KeyCode::KeyW) {
-        move_dir += Vec3::Y;
-    }
-    if keyboard_input.pressed(KeyCode::KeyS) {
-        move_dir -= Vec3::Y;
+    dash.active.tick(time.delta());
+    dash.cooldown.tick(time.delta());
+    // held rather than just pressed, that's all a replay has. Standing still there's no way to tell where to go.
+    if actions.pressed(Action::Dash) && dash.cooldown.finished() && actions.move_axis() != Vec2::ZERO {
+        dash.direction = actions.move_axis().normalize();
+        dash.active.reset();
+        dash.cooldown.reset();
     }
-
-    if move_dir.length_squared() > 0.05 {
-        move_dir = move_dir.normalize();
+    if dash.is_dashing() {
+        velocity.0 = (dash.direction * stats.get(Stat::MoveSpeed) * DASH_SPEED_MULT).extend(0.0);
+        events.send(TriggerAnimation(player_entity, AnimationType::Run));
+        return;
     }
-
-    move_dir *= modifiers.move_speed * modifiers.move_speed_mod;
+    
+    // already no longer than 1, a stick pushed part way walks slower
+    let move_dir = actions.move_axis().extend(0.0) * stats.get(Stat::MoveSpeed);
     velocity.0 = move_dir;
 
 
@@ -107,3 +153,16 @@ fn handle_move_ctl(
         events.send(TriggerAnimation(player_entity, AnimationType::Run));
     }
 }
+
+/// The player is only despawned once they're done `Dying`, that's when the run is over.
+fn end_run_on_player_despawn(
+    mut removed: RemovedComponents<PlayerComponent>,
+    q_player: Query<(), With<PlayerComponent>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // a restart swaps the old player for a new one, that's not the end of the (new) run
+    if removed.read().count() == 0 || !q_player.is_empty() { return; }
+
+    next_state.set(GameState::GameOverMenu);
+    info!("set 'game over menu' game state");
+}
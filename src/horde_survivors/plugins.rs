@@ -0,0 +1,44 @@
+use bevy::{app::PluginGroupBuilder, prelude::*};
+
+use crate::{
+    ActionInputPlugin, AssetHandlerPlugin, CameraPlugin, CollisionPlugin, DestructiblePlugin, EnemyPlugin,
+    ExperiencePlugin, HealthPlugin, LightingPlugin, MovementPlugin, ObstaclePlugin, PathfindingPlugin, PickupPlugin,
+    PlayerPlugin, ProjectilePlugin, ReplayMode, ReplayPlugin, RunRngPlugin, RunStatsPlugin, SchedulesPlugin,
+    SpatialIndexPlugin, StatePlugin, StatsPlugin, StatusPlugin, WeaponPlugin,
+};
+
+/// Every plugin the game is made of, on top of bevy's own (`DefaultPlugins`, or `headless_plugins`).
+pub struct GamePlugins(pub ReplayMode);
+impl PluginGroup for GamePlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(StatePlugin)
+            .add(SchedulesPlugin)
+            .add(ActionInputPlugin)
+            .add(RunRngPlugin)
+            .add(ReplayPlugin(self.0))
+
+            .add(LightingPlugin)
+            .add(CameraPlugin)
+
+            .add(AssetHandlerPlugin)
+
+            .add(MovementPlugin)
+            .add(CollisionPlugin)
+            .add(SpatialIndexPlugin)
+            .add(PathfindingPlugin)
+            .add(HealthPlugin)
+            .add(StatsPlugin)
+            .add(StatusPlugin)
+            .add(ProjectilePlugin)
+            .add(WeaponPlugin)
+            .add(PickupPlugin)
+            .add(DestructiblePlugin)
+            .add(ObstaclePlugin)
+            .add(ExperiencePlugin)
+            .add(RunStatsPlugin)
+
+            .add(PlayerPlugin)
+            .add(EnemyPlugin)
+    }
+}
@@ -5,8 +5,25 @@ pub use crate::horde_survivors::{
     assets::plugin::*,
     lighting::*,
     camera::*,
+    input::*,
+    rng::*,
     bundles::*,
     movement::*,
+    collision::*,
+    spatial::*,
+    pathfinding::*,
+    health::*,
+    stats::*,
+    status::*,
+    projectile::*,
+    weapon::*,
+    pickup::*,
+    destructible::*,
+    obstacle::*,
+    experience::*,
+    run_stats::*,
+    replay::*,
     player::*,
     enemy::*,
+    plugins::*,
 };
\ No newline at end of file
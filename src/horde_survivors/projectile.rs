@@ -0,0 +1,178 @@
+use bevy::prelude::*;
+
+use crate::{
+    Collider, CollisionLayers, CollisionStarted, DamageEvent, Dying, GameLoopSchedules, GameState,
+    ApplyStatus, Health, MovableObjectBundle, Obstacle, RunScoped, StatusEffect, Velocity, WeaponKind,
+    detect_collisions,
+    LAYER_DESTRUCTIBLE, LAYER_PLAYER, LAYER_STATIC,
+};
+use super::types::{AssetKey, SpawnMesh, ASSET_KEY_PROJECTILE};
+
+const DAGGER_MESH_SCALE: f32 = 0.5;
+// roughly hand height, so daggers don't skid along the floor
+const DAGGER_MESH_HEIGHT: f32 = 0.75;
+const PROJECTILE_SPIN_SPEED: f32 = 15.0;
+
+/// what projectiles fired at the player collide with, obstacles (torches included) stop them too.
+pub const HOSTILE_PROJECTILE_FILTERS: u32 = LAYER_PLAYER | LAYER_STATIC | LAYER_DESTRUCTIBLE;
+
+/// Stats a `Projectile` is launched with.
+#[derive(Debug, Clone, Copy)]
+pub struct ProjectileStats {
+    pub speed: f32,
+    /// seconds before the projectile despawns on its own.
+    pub lifetime: f32,
+    /// how many targets it can pass through after the first one it hits.
+    pub pierce: u32,
+    pub damage: f32,
+}
+
+#[derive(Component, Debug)]
+pub struct Projectile {
+    pub damage: f32,
+    hits_left: u32,
+    lifetime: Timer,
+}
+
+/// A status effect a `Projectile` applies to everything it hits.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct OnHitStatus(pub StatusEffect);
+
+pub struct ProjectilePlugin;
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(FixedUpdate,
+                spin_projectile_meshes
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates)
+            )
+            .add_systems(FixedUpdate,
+                projectile_hits
+                .after(detect_collisions)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::CollisionDetection)
+            )
+            .add_systems(FixedUpdate,
+                expire_projectiles
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::Despawn)
+            )
+        ;
+    }
+}
+
+/// Spawns a projectile at `origin`, travelling along `direction` (on the XY plane).
+pub(crate) fn spawn_projectile(
+    origin: Vec3,
+    direction: Vec2,
+    stats: ProjectileStats,
+    layers: CollisionLayers,
+    commands: &mut Commands,
+    events: &mut EventWriter<SpawnMesh>,
+) -> Entity {
+    let direction = direction.normalize_or_zero();
+    // same convention as `update_facing`, the local Y axis points along the velocity.
+    let facing = Quat::from_rotation_z(-f32::atan2(direction.x, direction.y));
+    let asset_key = AssetKey(ASSET_KEY_PROJECTILE.into());
+
+    let projectile = commands.spawn((
+        MovableObjectBundle {
+            transform: SpatialBundle {
+                transform: Transform::from_translation(origin).with_rotation(facing),
+                ..default()
+            },
+            velocity: Velocity(direction.extend(0.0) * stats.speed),
+            collider: Collider::capsule(0.25, 0.1),
+            layers,
+            ..default()
+        },
+        Projectile {
+            damage: stats.damage,
+            hits_left: stats.pierce.saturating_add(1),
+            lifetime: Timer::from_seconds(stats.lifetime, TimerMode::Once),
+        },
+        asset_key.clone(),
+        RunScoped,
+    )).id();
+
+    // the blade of the model already points along its Y axis.
+    let t = Transform::from_xyz(0.0, 0.0, DAGGER_MESH_HEIGHT)
+        .with_scale(Vec3::splat(DAGGER_MESH_SCALE));
+    events.send(SpawnMesh(projectile, asset_key, t));
+
+    projectile
+}
+
+/// spins the model around the direction it's travelling, rifling style.
+fn spin_projectile_meshes(
+    time: Res<Time>,
+    q_projectiles: Query<&Children, With<Projectile>>,
+    mut q_meshes: Query<&mut Transform, Without<Projectile>>,
+) {
+    let spin = Quat::from_rotation_y(PROJECTILE_SPIN_SPEED * time.delta_seconds());
+    for children in q_projectiles.iter() {
+        for child in children.iter() {
+            if let Ok(mut mesh_transform) = q_meshes.get_mut(*child) {
+                mesh_transform.rotation = spin * mesh_transform.rotation;
+            }
+        }
+    }
+}
+
+pub fn projectile_hits(
+    mut commands: Commands,
+    mut collisions: EventReader<CollisionStarted>,
+    mut q_projectiles: Query<(&mut Projectile, Option<&OnHitStatus>, Option<&WeaponKind>)>,
+    q_targets: Query<(), (With<Health>, Without<Dying>)>,
+    q_obstacles: Query<(), With<Obstacle>>,
+    mut damage: EventWriter<DamageEvent>,
+    mut statuses: EventWriter<ApplyStatus>,
+) {
+    for event in collisions.read() {
+        let (projectile_entity, target) = if q_projectiles.contains(event.0) {
+            (event.0, event.1)
+        } else if q_projectiles.contains(event.1) {
+            (event.1, event.0)
+        } else { continue; };
+
+        let (mut projectile, on_hit, weapon) = q_projectiles.get_mut(projectile_entity).expect("checked above");
+        // already used up by an earlier hit (or obstacle) this frame
+        if projectile.hits_left == 0 { continue; }
+        // obstacles stop projectiles, bar the player's weapons breaking a destructible one
+        if q_obstacles.contains(target) && !(weapon.is_some() && q_targets.contains(target)) {
+            projectile.hits_left = 0;
+            commands.entity(projectile_entity).despawn_recursive();
+            continue;
+        }
+        if !q_targets.contains(target) { continue; }
+
+        damage.send(DamageEvent {
+            target,
+            amount: projectile.damage,
+            source: Some(projectile_entity),
+            weapon: weapon.copied(),
+        });
+        if let Some(on_hit) = on_hit {
+            statuses.send(ApplyStatus(target, on_hit.0));
+        }
+
+        projectile.hits_left -= 1;
+        if projectile.hits_left == 0 {
+            commands.entity(projectile_entity).despawn_recursive();
+        }
+    }
+}
+
+fn expire_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_projectiles: Query<(Entity, &mut Projectile)>,
+) {
+    for (entity, mut projectile) in q_projectiles.iter_mut() {
+        if projectile.hits_left == 0 { continue; }
+        if projectile.lifetime.tick(time.delta()).just_finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
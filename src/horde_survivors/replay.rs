@@ -0,0 +1,375 @@
+use std::{
+    env,
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use bevy::{
+    app::{AppExit, PluginGroupBuilder, ScheduleRunnerPlugin},
+    prelude::*,
+    render::{settings::WgpuSettings, RenderPlugin},
+    time::TimeUpdateStrategy,
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ActionState, GameLoopSchedules, GameState, NextRunSeed, PlayerComponent, PlayerExperience, RunStats,
+    TickInput, UpgradeChoices, UpgradeChosen, WaveDirector,
+    gameplay_running,
+    FIXED_TIMESTEP_HZ,
+};
+use super::experience::apply_upgrade;
+
+/// Set once a replay can't be played back or doesn't end the way it was recorded.
+/// The `App` is gone by the time `main` finds out it exited, so it can't be a `Resource`.
+static REPLAY_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Picked from the command line: `--record <file>` or `--replay <file>`.
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub enum ReplayMode {
+    #[default]
+    Off,
+    /// the first run is recorded to this file, it starts right away
+    Record(PathBuf),
+    /// plays this file back headless, then exits (with an error if the run didn't end the same way)
+    Replay(PathBuf),
+}
+impl ReplayMode {
+    pub fn from_args() -> Self {
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match (arg.as_str(), args.next()) {
+                ("--record", Some(path)) => return ReplayMode::Record(path.into()),
+                ("--replay", Some(path)) => return ReplayMode::Replay(path.into()),
+                _ => (),
+            }
+        }
+        ReplayMode::Off
+    }
+
+    pub fn is_headless(&self) -> bool {
+        matches!(self, ReplayMode::Replay(_))
+    }
+}
+
+/// How a run ended up, recorded alongside the input so a replay can be checked against it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RunOutcome {
+    /// fixed ticks played
+    pub ticks: u32,
+    pub elapsed: f32,
+    pub level: u32,
+    pub kills: u32,
+    /// false if the run ended in a game over, true if it was abandoned
+    pub survived: bool,
+}
+
+/// Everything needed to play a run back: its seed, and what was held on each fixed tick.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    pub seed: u64,
+    /// run length encoded, each input was held for that many ticks
+    pub input: Vec<(u32, TickInput)>,
+    /// index into the `UpgradeChoices` picked, one per upgrade
+    pub upgrades: Vec<usize>,
+    pub outcome: Option<RunOutcome>,
+}
+impl Recording {
+    fn push_input(&mut self, input: TickInput) {
+        match self.input.last_mut() {
+            Some((ticks, last)) if *last == input => *ticks += 1,
+            _ => self.input.push((1, input)),
+        }
+    }
+
+    fn total_ticks(&self) -> u32 {
+        self.input.iter().map(|(ticks, _)| *ticks).sum()
+    }
+
+    fn load(path: &PathBuf) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        ron::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    fn save(&self, path: &PathBuf) -> Result<(), String> {
+        let text = ron::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(path, text).map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Progress {
+    #[default]
+    Waiting,
+    Running,
+    Done,
+}
+
+/// The run being recorded (or played back), and how far along it is.
+#[derive(Resource, Debug, Default)]
+struct ReplayState {
+    progress: Progress,
+    recording: Recording,
+    tick: u32,
+    /// replay only, where in `recording.input` (segment, ticks into it) and `recording.upgrades` it's up to
+    input_cursor: (usize, u32),
+    upgrade_cursor: usize,
+}
+
+
+/// Records or replays a run, depending on its `ReplayMode`. Does nothing when it's `ReplayMode::Off`.
+pub struct ReplayPlugin(pub ReplayMode);
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        if self.0 == ReplayMode::Off { return; }
+
+        if self.0.is_headless() {
+            // exactly one fixed tick per frame, as fast as it'll go
+            app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ)));
+        }
+
+        app
+            // Resources
+            .insert_resource(self.0.clone())
+            .insert_resource(ReplayState::default())
+
+            // Systems
+            .add_systems(OnEnter(GameState::MainMenu), (abandon_run, start_run).chain())
+            .add_systems(OnEnter(GameState::Initialize), abandon_run)
+            .add_systems(FixedUpdate,
+                step_input
+                .run_if(gameplay_running)
+                .before(GameLoopSchedules::ProcessInput)
+            )
+            .add_systems(OnEnter(GameState::GameOverMenu), end_run)
+        ;
+
+        if self.0.is_headless() {
+            app.add_systems(Update, replay_upgrade.run_if(in_state(GameState::LevelUp)).before(apply_upgrade));
+        } else {
+            app.add_systems(Update, record_upgrade.run_if(in_state(GameState::LevelUp)).before(apply_upgrade));
+        }
+    }
+}
+
+/// Whether the replay failed, for `main` to pick its exit code once the `App` has exited.
+pub fn replay_failed() -> bool {
+    REPLAY_FAILED.load(Ordering::Relaxed)
+}
+
+/// Plugins for replaying without a window (or a GPU).
+pub fn headless_plugins() -> PluginGroupBuilder {
+    DefaultPlugins.build()
+        .disable::<WinitPlugin>()
+        .set(WindowPlugin {
+            primary_window: None,
+            exit_condition: ExitCondition::DontExit,
+            ..default()
+        })
+        .set(RenderPlugin {
+            render_creation: WgpuSettings { backends: None, ..default() }.into(),
+            ..default()
+        })
+        .add(ScheduleRunnerPlugin::run_loop(Duration::ZERO))
+}
+
+/// Skips the main menu, straight into the run being recorded (or replayed).
+fn start_run(
+    mode: Res<ReplayMode>,
+    mut state: ResMut<ReplayState>,
+    mut next_seed: ResMut<NextRunSeed>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if state.progress != Progress::Waiting { return; }
+
+    let recording = match &*mode {
+        ReplayMode::Off => return,
+        ReplayMode::Record(_) => Recording { seed: rand::thread_rng().gen(), ..default() },
+        ReplayMode::Replay(path) => match Recording::load(path) {
+            Ok(recording) => recording,
+            Err(e) => {
+                error!("couldn't read the replay {:?}: {}", path, e);
+                fail_replay(&mut exit);
+                return;
+            },
+        },
+    };
+
+    info!("starting run with seed {}", recording.seed);
+    next_seed.0 = Some(recording.seed);
+    *state = ReplayState {
+        progress: Progress::Running,
+        recording,
+        ..default()
+    };
+    next_state.set(GameState::Initialize);
+}
+
+/// Records (or plays back) this tick's input, and notices the run ending before the game over screen does.
+fn step_input(
+    mode: Res<ReplayMode>,
+    mut state: ResMut<ReplayState>,
+    mut actions: ResMut<ActionState>,
+    director: Res<WaveDirector>,
+    stats: Res<RunStats>,
+    q_player: Query<&PlayerExperience, With<PlayerComponent>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if state.progress != Progress::Running { return; }
+
+    // the rest of the frame's ticks still run once the player's gone, but they don't count
+    let player = q_player.get_single().ok();
+    let out_of_input = matches!(*mode, ReplayMode::Replay(_)) && state.tick >= state.recording.total_ticks();
+    if player.is_none() || out_of_input {
+        let outcome = capture_outcome(state.tick, &director, &stats, player);
+        finish(&mode, &mut state, outcome, &mut exit);
+        return;
+    }
+
+    match &*mode {
+        ReplayMode::Record(_) => {
+            // quantized for live play too, so the recording holds exactly what the run saw
+            let input = actions.tick_input();
+            actions.apply_tick_input(input);
+            state.recording.push_input(input);
+        },
+        ReplayMode::Replay(_) => {
+            let (segment, used) = state.input_cursor;
+            let (ticks, input) = state.recording.input[segment];
+            actions.apply_tick_input(input);
+            state.input_cursor = if used + 1 >= ticks { (segment + 1, 0) } else { (segment, used + 1) };
+        },
+        ReplayMode::Off => (),
+    }
+    state.tick += 1;
+}
+
+/// Upgrades are picked between ticks, so they're kept apart from the input.
+fn record_upgrade(
+    mut state: ResMut<ReplayState>,
+    choices: Res<UpgradeChoices>,
+    mut chosen: EventReader<UpgradeChosen>,
+) {
+    // `apply_upgrade` only takes the first one too
+    let upgrade = if let Some(event) = chosen.read().next() {
+        event.0
+    } else { return; };
+    chosen.clear();
+    if state.progress != Progress::Running { return; }
+
+    if let Some(n) = choices.0.iter().position(|choice| *choice == upgrade) {
+        state.recording.upgrades.push(n);
+    }
+}
+
+fn replay_upgrade(
+    mut state: ResMut<ReplayState>,
+    choices: Res<UpgradeChoices>,
+    mut picks: EventWriter<UpgradeChosen>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if state.progress != Progress::Running { return; }
+    if choices.0.is_empty() { return; }
+
+    let n = state.upgrade_cursor;
+    let upgrade = if let Some(upgrade) = state.recording.upgrades.get(n).and_then(|n| choices.0.get(*n)) {
+        *upgrade
+    } else {
+        error!("replay desynced, no upgrade recorded for pick {}", n + 1);
+        state.progress = Progress::Done;
+        fail_replay(&mut exit);
+        return;
+    };
+    picks.send(UpgradeChosen(upgrade));
+    state.upgrade_cursor += 1;
+}
+
+/// Game over, unless `step_input` already saw it coming.
+fn end_run(
+    mode: Res<ReplayMode>,
+    mut state: ResMut<ReplayState>,
+    director: Res<WaveDirector>,
+    stats: Res<RunStats>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if state.progress != Progress::Running { return; }
+
+    let outcome = capture_outcome(state.tick, &director, &stats, None);
+    finish(&mode, &mut state, outcome, &mut exit);
+}
+
+/// Quitting to the menu (or restarting) part way through still saves what was recorded.
+fn abandon_run(
+    mode: Res<ReplayMode>,
+    mut state: ResMut<ReplayState>,
+    director: Res<WaveDirector>,
+    stats: Res<RunStats>,
+    q_player: Query<&PlayerExperience, With<PlayerComponent>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if state.progress != Progress::Running { return; }
+    // `start_run` just sent us here
+    if state.tick == 0 { return; }
+
+    let outcome = capture_outcome(state.tick, &director, &stats, q_player.get_single().ok());
+    finish(&mode, &mut state, outcome, &mut exit);
+}
+
+fn capture_outcome(
+    ticks: u32,
+    director: &WaveDirector,
+    stats: &RunStats,
+    player: Option<&PlayerExperience>,
+) -> RunOutcome {
+    RunOutcome {
+        ticks,
+        elapsed: director.elapsed,
+        level: player.map_or(stats.level, |xp| xp.level),
+        kills: stats.total_kills(),
+        survived: player.is_some(),
+    }
+}
+
+/// Saves the recording, or checks the replay against it (and exits).
+fn finish(
+    mode: &ReplayMode,
+    state: &mut ReplayState,
+    outcome: RunOutcome,
+    exit: &mut EventWriter<AppExit>,
+) {
+    state.progress = Progress::Done;
+
+    match mode {
+        ReplayMode::Record(path) => {
+            state.recording.outcome = Some(outcome);
+            match state.recording.save(path) {
+                Ok(()) => info!("recorded {} ticks to {:?}: {:?}", outcome.ticks, path, outcome),
+                Err(e) => error!("couldn't save the recording to {:?}: {}", path, e),
+            }
+        },
+        ReplayMode::Replay(_) => {
+            match state.recording.outcome {
+                Some(expected) if expected == outcome => {
+                    info!("replay matched: {:?}", outcome);
+                    exit.send(AppExit);
+                },
+                expected => {
+                    error!("replay desynced, expected {:?} but got {:?}", expected, outcome);
+                    fail_replay(exit);
+                },
+            }
+        },
+        ReplayMode::Off => (),
+    }
+}
+
+fn fail_replay(exit: &mut EventWriter<AppExit>) {
+    REPLAY_FAILED.store(true, Ordering::Relaxed);
+    exit.send(AppExit);
+}
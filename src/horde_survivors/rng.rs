@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::GameState;
+
+
+/// Every random roll that affects a run comes from here, so the same seed (and the same input) plays out the same run.
+#[derive(Resource, Debug)]
+pub struct RunRng(pub StdRng);
+impl Default for RunRng {
+    fn default() -> Self {
+        Self(StdRng::seed_from_u64(0))
+    }
+}
+
+/// The seed for the next run, a random one is picked if this is empty. Taken when the run starts.
+#[derive(Resource, Debug, Default)]
+pub struct NextRunSeed(pub Option<u64>);
+
+
+pub struct RunRngPlugin;
+impl Plugin for RunRngPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Resources
+            .insert_resource(RunRng::default())
+            .insert_resource(NextRunSeed::default())
+
+            // Systems
+            .add_systems(OnEnter(GameState::Initialize), seed_run_rng)
+        ;
+    }
+}
+
+fn seed_run_rng(
+    mut run_rng: ResMut<RunRng>,
+    mut next_seed: ResMut<NextRunSeed>,
+) {
+    let seed = next_seed.0.take().unwrap_or_else(|| rand::thread_rng().gen());
+    info!("run seed: {}", seed);
+    run_rng.0 = StdRng::seed_from_u64(seed);
+}
@@ -0,0 +1,82 @@
+use bevy::{
+    prelude::*,
+    utils::hashbrown::HashMap,
+};
+
+use crate::{
+    DamageTaken, DeathEvent, EnemyName, GameLoopSchedules, GameState, PlayerComponent, PlayerExperience, WaveDirector,
+    WeaponKind,
+    direct_waves, reset_run_resource,
+};
+use super::ui::game_over::GameOverMenuPlugin;
+
+
+/// What happened over the current run, shown on the game over screen.
+#[derive(Resource, Debug, Default)]
+pub struct RunStats {
+    /// seconds survived, set once the player dies
+    pub time: f32,
+    /// the player's level when they died
+    pub level: u32,
+    /// by `EnemyName`
+    pub kills: HashMap<String, u32>,
+    pub weapon_damage: HashMap<WeaponKind, f32>,
+}
+impl RunStats {
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+}
+
+pub struct RunStatsPlugin;
+impl Plugin for RunStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(GameOverMenuPlugin)
+
+            // Resources
+            .insert_resource(RunStats::default())
+
+            // Systems
+            .add_systems(OnEnter(GameState::Initialize), reset_run_resource::<RunStats>)
+            .add_systems(FixedUpdate,
+                (tally_damage, tally_deaths)
+                .after(direct_waves)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::Spawn)
+            )
+        ;
+    }
+}
+
+/// only damage dealt to enemies counts, breaking torches doesn't.
+fn tally_damage(
+    mut stats: ResMut<RunStats>,
+    mut events: EventReader<DamageTaken>,
+    q_enemies: Query<(), With<EnemyName>>,
+) {
+    for event in events.read() {
+        let weapon = if let Some(kind) = event.weapon {
+            kind
+        } else { continue; };
+        if !q_enemies.contains(event.target) { continue; }
+        *stats.weapon_damage.entry(weapon).or_default() += event.amount;
+    }
+}
+
+fn tally_deaths(
+    mut stats: ResMut<RunStats>,
+    mut deaths: EventReader<DeathEvent>,
+    director: Res<WaveDirector>,
+    q_enemies: Query<&EnemyName>,
+    q_player: Query<&PlayerExperience, With<PlayerComponent>>,
+) {
+    for event in deaths.read() {
+        if let Ok(name) = q_enemies.get(event.0) {
+            *stats.kills.entry(name.0.clone()).or_default() += 1;
+        } else if let Ok(experience) = q_player.get(event.0) {
+            stats.time = director.elapsed;
+            stats.level = experience.level;
+        }
+    }
+}
@@ -1,4 +1,12 @@
-use bevy::prelude::*;
+use bevy::{
+    ecs::schedule::ExecutorKind,
+    prelude::*,
+};
+
+use crate::GameState;
+
+/// Gameplay steps at this rate no matter the frame rate, so a run plays out the same every time.
+pub const FIXED_TIMESTEP_HZ: f64 = 60.0;
 
 
 #[derive(SystemSet, Debug, Clone, Hash, PartialEq, Eq)]
@@ -17,18 +25,33 @@ pub enum GameLoopSchedules {
 pub struct SchedulesPlugin;
 impl Plugin for SchedulesPlugin {
     fn build(&self, app: &mut App) {
-        app.configure_sets(Update, (
+        app.insert_resource(Time::<Fixed>::from_hz(FIXED_TIMESTEP_HZ))
+        // systems that aren't ordered against each other would otherwise run in whatever order the threads get to them
+        .edit_schedule(FixedUpdate, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        })
+        .configure_sets(FixedUpdate, (
             GameLoopSchedules::ProcessInput,
             GameLoopSchedules::Spawn,
             GameLoopSchedules::PostSpawn,
             GameLoopSchedules::EntityUpdates,
             GameLoopSchedules::CollisionDetection,
             GameLoopSchedules::Despawn,
-        ).chain())
-        .add_systems(Update, 
+        ).chain().run_if(gameplay_running))
+        .add_systems(FixedUpdate, 
             apply_deferred
             .after(GameLoopSchedules::Spawn)
             .before(GameLoopSchedules::PostSpawn)
         );
     }
 }
+
+/// Several fixed ticks can run in one frame, but state changes only happen between frames.
+/// Once a tick has asked to leave `Playing` (ie. for a level up), the rest of that frame's ticks are skipped,
+/// otherwise how far the run gets before the change would depend on the frame rate.
+pub fn gameplay_running(
+    state: Res<State<GameState>>,
+    next_state: Res<NextState<GameState>>,
+) -> bool {
+    *state.get() == GameState::Playing && next_state.0.is_none()
+}
@@ -0,0 +1,329 @@
+use bevy::{
+    math::{IVec2, Vec2},
+    ecs::entity::Entity,
+    utils::hashbrown::HashMap,
+};
+
+/// An `Entity` as it was indexed by the `SpatialGrid`.
+#[derive(Debug, Clone, Copy)]
+pub struct SpatialEntry {
+    pub entity: Entity,
+    pub position: Vec2,
+    /// bounding radius of the entity (ie. its `Collider`), used by the collision broad phase.
+    pub radius: f32,
+    /// collision layer memberships, queries only return entries matching their `mask`.
+    pub layers: u32,
+}
+
+/// A uniform grid over the XY plane, bucketing entities by the cell their center falls in.
+///
+/// Cells are only allocated where something has been inserted, so the grid is unbounded.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<SpatialEntry>>,
+    len: usize,
+    max_radius: f32,
+    min_cell: IVec2,
+    max_cell: IVec2,
+}
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0, "cell size must be positive");
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            len: 0,
+            max_radius: 0.0,
+            min_cell: IVec2::MAX,
+            max_cell: IVec2::MIN,
+        }
+    }
+
+    // only the benchmark (and tests) read this, `is_empty` is enough for the game
+    #[cfg(any(test, feature = "bench"))]
+    pub fn len(&self) -> usize { self.len }
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+    /// largest `radius` of any entry currently in the grid.
+    pub fn max_radius(&self) -> f32 { self.max_radius }
+
+    /// Empties the grid, but keeps the allocations for cells that were used since the last `clear`.
+    /// Cells that stayed empty are dropped, so the grid doesn't grow forever as things move around.
+    pub fn clear(&mut self) {
+        self.cells.retain(|_, entries| {
+            let keep = !entries.is_empty();
+            entries.clear();
+            keep
+        });
+        self.len = 0;
+        self.max_radius = 0.0;
+        self.min_cell = IVec2::MAX;
+        self.max_cell = IVec2::MIN;
+    }
+
+    pub fn insert(&mut self, entry: SpatialEntry) {
+        let cell = self.cell_of(entry.position);
+        self.cells.entry(cell).or_default().push(entry);
+
+        self.len += 1;
+        self.max_radius = self.max_radius.max(entry.radius);
+        self.min_cell = self.min_cell.min(cell);
+        self.max_cell = self.max_cell.max(cell);
+    }
+
+    pub fn cell_of(&self, point: Vec2) -> IVec2 {
+        (point / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Calls `f` for every entry (matching `mask`) whose center is within `radius` of `point`.
+    pub fn for_each_in_radius(&self, point: Vec2, radius: f32, mask: u32, mut f: impl FnMut(&SpatialEntry)) {
+        let radius_sq = radius * radius;
+        self.for_each_in_cells(point - Vec2::splat(radius), point + Vec2::splat(radius), |entry| {
+            if entry.layers & mask == 0 { return; }
+            if entry.position.distance_squared(point) > radius_sq { return; }
+            f(entry);
+        });
+    }
+
+    /// Entities (matching `mask`) whose center is within `radius` of `point`.
+    /// Only for the benchmark (and tests), systems use `for_each_in_radius` to avoid the allocation.
+    #[cfg(any(test, feature = "bench"))]
+    pub fn within_radius(&self, point: Vec2, radius: f32, mask: u32) -> Vec<Entity> {
+        let mut found = Vec::new();
+        self.for_each_in_radius(point, radius, mask, |entry| found.push(entry.entity));
+        found
+    }
+
+    /// Every entry (matching `mask`) whose center lies inside the axis aligned box `min`..=`max`.
+    /// Only for the benchmark (and tests).
+    #[cfg(any(test, feature = "bench"))]
+    pub fn in_aabb(&self, min: Vec2, max: Vec2, mask: u32) -> Vec<Entity> {
+        let mut found = Vec::new();
+        self.for_each_in_cells(min, max, |entry| {
+            if entry.layers & mask == 0 { return; }
+            let p = entry.position;
+            if p.x < min.x || p.y < min.y || p.x > max.x || p.y > max.y { return; }
+            found.push(entry.entity);
+        });
+        found
+    }
+
+    /// Up to `k` entries (matching `mask`) closest to `point`, sorted nearest first.
+    /// The `f32` is the squared distance to `point`.
+    pub fn k_nearest(&self, point: Vec2, k: usize, mask: u32) -> Vec<(SpatialEntry, f32)> {
+        let mut best: Vec<(SpatialEntry, f32)> = Vec::with_capacity(k + 1);
+        if k == 0 || self.is_empty() { return best; }
+
+        let center = self.cell_of(point);
+        // the furthest ring that could still hold anything
+        let max_ring = (center - self.min_cell).abs()
+            .max((center - self.max_cell).abs())
+            .max_element();
+
+        for ring in 0..=max_ring {
+            self.for_each_in_ring(center, ring, |entry| {
+                if entry.layers & mask == 0 { return; }
+
+                let dist_sq = entry.position.distance_squared(point);
+                if best.len() == k && dist_sq >= best[k - 1].1 { return; }
+
+                let idx = best.partition_point(|(_, d)| *d <= dist_sq);
+                best.insert(idx, (*entry, dist_sq));
+                best.truncate(k);
+            });
+
+            // anything in the next ring is at least `ring * cell_size` away.
+            if best.len() == k {
+                let next_ring_dist = ring as f32 * self.cell_size;
+                if best[k - 1].1 <= next_ring_dist * next_ring_dist { break; }
+            }
+        }
+
+        best
+    }
+
+    pub fn nearest(&self, point: Vec2, mask: u32) -> Option<SpatialEntry> {
+        self.k_nearest(point, 1, mask).first().map(|(entry, _)| *entry)
+    }
+
+    fn for_each_in_cells(&self, min: Vec2, max: Vec2, mut f: impl FnMut(&SpatialEntry)) {
+        let min_cell = self.cell_of(min).max(self.min_cell);
+        let max_cell = self.cell_of(max).min(self.max_cell);
+
+        for y in min_cell.y..=max_cell.y {
+            for x in min_cell.x..=max_cell.x {
+                if let Some(entries) = self.cells.get(&IVec2::new(x, y)) {
+                    entries.iter().for_each(&mut f);
+                }
+            }
+        }
+    }
+
+    /// visits the cells at exactly `ring` (chebyshev distance) away from `center`.
+    fn for_each_in_ring(&self, center: IVec2, ring: i32, mut f: impl FnMut(&SpatialEntry)) {
+        let mut visit = |x: i32, y: i32| {
+            if let Some(entries) = self.cells.get(&IVec2::new(x, y)) {
+                entries.iter().for_each(&mut f);
+            }
+        };
+
+        if ring == 0 {
+            visit(center.x, center.y);
+            return;
+        }
+
+        for x in (center.x - ring)..=(center.x + ring) {
+            visit(x, center.y - ring);
+            visit(x, center.y + ring);
+        }
+        for y in (center.y - ring + 1)..=(center.y + ring - 1) {
+            visit(center.x - ring, y);
+            visit(center.x + ring, y);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CELL_SIZE: f32 = 2.0;
+
+    /// a deterministic scatter over -10..10, plus points on, and either side of, every cell boundary
+    /// around the origin. Every other entry is on layer 2 instead of 1.
+    fn entries() -> Vec<SpatialEntry> {
+        let mut state: u32 = 0x2545_F491;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state as f32 / u32::MAX as f32) * 20.0 - 10.0
+        };
+        let mut positions: Vec<Vec2> = (0..400).map(|_| Vec2::new(next(), next())).collect();
+
+        for n in -3..=3 {
+            let edge = n as f32 * CELL_SIZE;
+            for offset in [-0.001, 0.0, 0.001] {
+                positions.push(Vec2::new(edge + offset, 0.5));
+                positions.push(Vec2::new(-0.5, edge + offset));
+                positions.push(Vec2::splat(edge + offset));
+            }
+        }
+
+        positions.into_iter().enumerate().map(|(n, position)| SpatialEntry {
+            entity: Entity::from_raw(n as u32),
+            position,
+            radius: 0.5,
+            layers: if n % 2 == 0 { 1 } else { 2 },
+        }).collect()
+    }
+
+    fn grid(entries: &[SpatialEntry]) -> SpatialGrid {
+        let mut grid = SpatialGrid::new(CELL_SIZE);
+        entries.iter().for_each(|e| grid.insert(*e));
+        grid
+    }
+
+    fn sorted(mut entities: Vec<Entity>) -> Vec<Entity> {
+        entities.sort();
+        entities
+    }
+
+    /// query points, including ones sitting right on cell boundaries and outside of everything
+    fn points() -> Vec<Vec2> {
+        vec![
+            Vec2::ZERO,
+            Vec2::new(CELL_SIZE, -CELL_SIZE),
+            Vec2::new(1.999, 2.001),
+            Vec2::new(-3.3, 7.9),
+            Vec2::new(9.5, -9.5),
+            Vec2::new(40.0, 40.0),
+        ]
+    }
+
+    #[test]
+    fn within_radius_matches_a_linear_scan() {
+        let entries = entries();
+        let grid = grid(&entries);
+        assert_eq!(grid.len(), entries.len());
+
+        for point in points() {
+            // radii that end on, just short of and just past cell boundaries
+            for radius in [0.0, 1.0, CELL_SIZE, 2.001, 3.999, 7.5, 50.0] {
+                for mask in [1, 2, u32::MAX] {
+                    let expected: Vec<_> = entries.iter()
+                        .filter(|e| e.layers & mask != 0 && e.position.distance_squared(point) <= radius * radius)
+                        .map(|e| e.entity)
+                        .collect();
+                    assert_eq!(
+                        sorted(grid.within_radius(point, radius, mask)), sorted(expected),
+                        "{point} radius {radius} mask {mask}",
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn in_aabb_matches_a_linear_scan() {
+        let entries = entries();
+        let grid = grid(&entries);
+
+        let boxes = [
+            (Vec2::ZERO, Vec2::splat(CELL_SIZE)),
+            (Vec2::new(-2.001, -0.001), Vec2::new(0.001, 4.0)),
+            (Vec2::new(-7.3, -1.0), Vec2::new(3.2, 9.9)),
+            (Vec2::splat(-20.0), Vec2::splat(20.0)),
+            (Vec2::splat(30.0), Vec2::splat(40.0)),
+        ];
+        for (min, max) in boxes {
+            for mask in [1, 2, u32::MAX] {
+                let expected: Vec<_> = entries.iter()
+                    .filter(|e| e.layers & mask != 0)
+                    .filter(|e| e.position.cmpge(min).all() && e.position.cmple(max).all())
+                    .map(|e| e.entity)
+                    .collect();
+                assert_eq!(sorted(grid.in_aabb(min, max, mask)), sorted(expected), "{min}..{max} mask {mask}");
+            }
+        }
+    }
+
+    #[test]
+    fn k_nearest_matches_a_linear_scan() {
+        let entries = entries();
+        let grid = grid(&entries);
+
+        for point in points() {
+            for k in [1, 3, 17, entries.len() + 5] {
+                for mask in [1, 2, u32::MAX] {
+                    let mut expected: Vec<f32> = entries.iter()
+                        .filter(|e| e.layers & mask != 0)
+                        .map(|e| e.position.distance_squared(point))
+                        .collect();
+                    expected.sort_by(f32::total_cmp);
+                    expected.truncate(k);
+
+                    let found = grid.k_nearest(point, k, mask);
+                    // ties can come back in either order, so only the distances are compared
+                    let distances: Vec<f32> = found.iter().map(|(_, d)| *d).collect();
+                    assert_eq!(distances, expected, "{point} k {k} mask {mask}");
+                    for (entry, dist_sq) in found {
+                        assert!(entry.layers & mask != 0);
+                        assert_eq!(entry.position.distance_squared(point), dist_sq);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn nothing_is_found_in_an_empty_grid() {
+        let mut grid = grid(&entries());
+        grid.clear();
+
+        assert!(grid.is_empty());
+        assert!(grid.k_nearest(Vec2::ZERO, 3, u32::MAX).is_empty());
+        assert!(grid.within_radius(Vec2::ZERO, 50.0, u32::MAX).is_empty());
+        assert!(grid.in_aabb(Vec2::splat(-20.0), Vec2::splat(20.0), u32::MAX).is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+
+use crate::{Collider, CollisionLayers, GameLoopSchedules, GameState, gameplay_running, reset_run_resource};
+
+mod grid;
+pub use grid::*;
+
+const SPATIAL_CELL_SIZE: f32 = 2.0;
+
+/// Uniform grid of every `Entity` with a `Collider`, rebuilt each frame once movement has been applied
+/// (after `EntityUpdates`, before `CollisionDetection`).
+#[derive(Resource, Debug)]
+pub struct SpatialIndex(pub SpatialGrid);
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self(SpatialGrid::new(SPATIAL_CELL_SIZE))
+    }
+}
+
+pub struct SpatialIndexPlugin;
+impl Plugin for SpatialIndexPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Resources
+            .insert_resource(SpatialIndex::default())
+
+            // Systems
+            .add_systems(OnEnter(GameState::Initialize), reset_run_resource::<SpatialIndex>)
+            .add_systems(FixedUpdate,
+                rebuild_spatial_index
+                .run_if(gameplay_running)
+                .after(GameLoopSchedules::EntityUpdates)
+                .before(GameLoopSchedules::CollisionDetection)
+            )
+        ;
+    }
+}
+
+fn rebuild_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    q_indexed: Query<(Entity, &Transform, &Collider, &CollisionLayers)>,
+) {
+    index.0.clear();
+    for (entity, transform, collider, layers) in q_indexed.iter() {
+        index.0.insert(SpatialEntry {
+            entity,
+            position: transform.translation.truncate(),
+            radius: collider.bounding_radius(),
+            layers: layers.memberships,
+        });
+    }
+}
@@ -1,61 +1,204 @@
 use bevy::prelude::*;
 
-use crate::GameLoopSchedules;
+use crate::{Action, ActionState};
 
 #[derive(States, Debug, Default, Clone, Hash, Eq, PartialEq)]
 pub enum GameState {
     #[default]
     Loading,
-    // MainMenu,
+    MainMenu,
     Initialize,
     Playing,
+    // gameplay is paused while an upgrade is picked
+    LevelUp,
     PauseMenu,
-    // GameOverMenu,
+    // the player died, showing the run summary
+    GameOverMenu,
 }
 
+/// Anything that only lives for one run, it's despawned (along with its children) when the run ends.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct RunScoped;
+
 pub struct StatePlugin;
 impl Plugin for StatePlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<GameState>()
-            .add_systems(OnEnter(GameState::Initialize), advance_initialization)
+            .add_systems(OnEnter(GameState::Initialize), (despawn_run_entities, advance_initialization))
+            // the game over screen keeps the last run in the background, so it's only cleared once we're done with it
+            .add_systems(OnEnter(GameState::MainMenu), despawn_run_entities)
             .add_systems(Update, 
                 process_pause_events
                 .run_if(in_state(GameState::Playing))
-                .before(GameLoopSchedules::ProcessInput)
-            )
-            .add_systems(Update, 
-                process_pause_events
-                .run_if(in_state(GameState::PauseMenu))
-                .before(GameLoopSchedules::ProcessInput)
             )
+            // the pause menu handles its own way back out
+            .add_systems(OnEnter(GameState::PauseMenu), pause_virtual_time)
+            .add_systems(OnExit(GameState::PauseMenu), resume_virtual_time)
             ;
     }
 }
 
 fn process_pause_events(
     mut next_state: ResMut<NextState<GameState>>,
-    state: Res<State<GameState>>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
 ) {
-    match state.get() {
-        GameState::Playing => {
-            if keyboard_input.just_pressed(KeyCode::Escape) {
-                next_state.set(GameState::PauseMenu);
-                info!("set 'pause menu' game state");
-            }
-        },
-        GameState::PauseMenu => {
-            if keyboard_input.just_pressed(KeyCode::Escape) {
-                next_state.set(GameState::Playing);
-                info!("set 'playing' game state");
-            }
-        },
-        _ => (),
+    if actions.just_pressed(Action::Pause) {
+        next_state.set(GameState::PauseMenu);
+        info!("set 'pause menu' game state");
     }
 }
 
+/// Everything ticked off `Time` stops while paused, the `AnimationPlayer`s included.
+fn pause_virtual_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_virtual_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn despawn_run_entities(
+    mut commands: Commands,
+    q_scoped: Query<Entity, With<RunScoped>>,
+) {
+    for entity in q_scoped.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Puts a run's resource back how it started, add it to `OnEnter(GameState::Initialize)` for each one.
+pub fn reset_run_resource<R: Resource + Default>(mut commands: Commands) {
+    commands.insert_resource(R::default());
+}
+
 fn advance_initialization(
     mut next_state: ResMut<NextState<GameState>>,
 ) {
     next_state.set(GameState::Playing);
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::{
+        headless_plugins, ActiveCollisions, DamageEvent, GamePlugins, NextRunSeed, PlayerComponent, PlayerExperience,
+        ReplayMode, RunStats, SpatialIndex, UpgradeChoices, UpgradeChosen, WaveDirector,
+        FIXED_TIMESTEP_HZ,
+    };
+
+    const SEED: u64 = 7;
+    /// fixed ticks into a run before it's compared with another
+    const SETTLE_TICKS: u32 = 120;
+    /// how long the first run is played for before the player is killed off
+    const RUN_TICKS: u32 = 60 * 45;
+
+    /// The whole game, headless, one fixed tick per update.
+    fn game() -> App {
+        let mut app = App::new();
+        app
+            .add_plugins(headless_plugins())
+            .add_plugins(GamePlugins(ReplayMode::Off))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ)));
+        app.finish();
+        app.cleanup();
+        app
+    }
+
+    fn state(app: &App) -> GameState {
+        app.world.resource::<State<GameState>>().get().clone()
+    }
+
+    fn update_until(app: &mut App, state: GameState) {
+        let start = Instant::now();
+        while self::state(app) != state {
+            assert!(start.elapsed() < Duration::from_secs(60), "never got to {:?}, stuck in {:?}", state, self::state(app));
+            app.update();
+        }
+    }
+
+    /// Starts a run the way the menus do, and plays its first few ticks.
+    fn start_run(app: &mut App) {
+        app.world.resource_mut::<NextRunSeed>().0 = Some(SEED);
+        app.world.resource_mut::<NextState<GameState>>().set(GameState::Initialize);
+        update_until(app, GameState::Playing);
+        for _ in 0..SETTLE_TICKS {
+            app.update();
+        }
+        assert_eq!(state(app), GameState::Playing);
+    }
+
+    /// Picks the first upgrade whenever the player levels up, like a replay would. Stops early on a game over.
+    fn play(app: &mut App, ticks: u32) {
+        for _ in 0..ticks {
+            if state(app) == GameState::GameOverMenu { return; }
+            if state(app) == GameState::LevelUp {
+                let upgrade = app.world.resource::<UpgradeChoices>().0[0];
+                app.world.send_event(UpgradeChosen(upgrade));
+            }
+            app.update();
+        }
+    }
+
+    /// Everything a run leaves behind, entities are only counted since their ids aren't reused as is.
+    #[derive(Debug, PartialEq)]
+    struct Snapshot {
+        entities: u32,
+        run_scoped: usize,
+        player_experience: Option<String>,
+        run_stats: String,
+        wave_director: String,
+        upgrade_choices: usize,
+        active_collisions: usize,
+        indexed: usize,
+    }
+    fn snapshot(app: &mut App) -> Snapshot {
+        let world = &mut app.world;
+        let player_experience = world.query_filtered::<&PlayerExperience, With<PlayerComponent>>().get_single(world);
+        Snapshot {
+            player_experience: player_experience.ok().map(|e| format!("{:?}", e)),
+            entities: world.entities().len(),
+            run_scoped: world.query_filtered::<(), With<RunScoped>>().iter(world).count(),
+            run_stats: format!("{:?}", world.resource::<RunStats>()),
+            wave_director: format!("{:?}", world.resource::<WaveDirector>()),
+            upgrade_choices: world.resource::<UpgradeChoices>().0.len(),
+            active_collisions: world.resource::<ActiveCollisions>().0.len(),
+            indexed: world.resource::<SpatialIndex>().0.len(),
+        }
+    }
+
+    #[test]
+    fn a_new_run_starts_from_scratch() {
+        let mut app = game();
+        update_until(&mut app, GameState::MainMenu);
+
+        start_run(&mut app);
+        let fresh = snapshot(&mut app);
+        assert!(fresh.run_scoped > 0);
+
+        play(&mut app, RUN_TICKS);
+        assert!(app.world.resource::<RunStats>().total_kills() > 0);
+        assert_ne!(snapshot(&mut app), fresh);
+
+        // dying is the only way a run ends
+        let start = Instant::now();
+        while state(&app) != GameState::GameOverMenu {
+            assert!(start.elapsed() < Duration::from_secs(60), "the player never died");
+            let player = app.world.query_filtered::<Entity, With<PlayerComponent>>().get_single(&app.world);
+            if let Ok(player) = player {
+                app.world.send_event(DamageEvent { target: player, amount: f32::MAX, source: None, weapon: None });
+            }
+            play(&mut app, 1);
+        }
+        // the game over screen still shows the run behind it
+        assert!(app.world.query_filtered::<(), With<RunScoped>>().iter(&app.world).count() > 0);
+        assert!(app.world.resource::<RunStats>().time > 0.0);
+
+        // retried from the game over screen, with the same seed it should be the exact same start
+        start_run(&mut app);
+        assert_eq!(snapshot(&mut app), fresh);
+    }
+}
@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+
+use crate::{Dying, GameLoopSchedules, GameState, Health};
+
+// damage left over after armor, so armor never makes something invincible
+const MIN_DAMAGE_AFTER_ARMOR: f32 = 1.0;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stat {
+    MaxHealth,
+    /// health restored per second
+    Regeneration,
+    /// flat reduction to every hit taken
+    Armor,
+    MoveSpeed,
+    /// damage multiplier
+    Might,
+    /// weapon area / range multiplier
+    Area,
+    /// weapon cooldown multiplier, lower is faster
+    Cooldown,
+    /// extra projectiles fired by weapons that fire more than one
+    ProjectileCount,
+    /// pickups inside this radius get pulled in
+    MagnetRadius,
+    /// drop chance multiplier
+    Luck,
+}
+impl Stat {
+    const COUNT: usize = 10;
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stat::MaxHealth => "Max health",
+            Stat::Regeneration => "Regeneration",
+            Stat::Armor => "Armor",
+            Stat::MoveSpeed => "Move speed",
+            Stat::Might => "Might",
+            Stat::Area => "Area",
+            Stat::Cooldown => "Cooldown",
+            Stat::ProjectileCount => "Amount",
+            Stat::MagnetRadius => "Pickup radius",
+            Stat::Luck => "Luck",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModifierOp {
+    /// added to the base value
+    Add(f32),
+    /// multiplies the total, after everything is added
+    Mul(f32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifierSource {
+    /// permanent, ie. picked on level up
+    Passive,
+    // nothing grants one yet, `add_modifier` treats them the same as debuffs
+    #[allow(dead_code)]
+    Buff,
+    /// ie. a slow, see `apply_status_events`
+    Debuff,
+}
+
+#[derive(Debug, Clone)]
+pub struct StatModifier {
+    pub stat: Stat,
+    pub op: ModifierOp,
+    pub source: ModifierSource,
+    /// `None` lasts forever
+    duration: Option<Timer>,
+}
+impl StatModifier {
+    pub fn new(stat: Stat, op: ModifierOp, source: ModifierSource) -> Self {
+        Self { stat, op, source, duration: None }
+    }
+
+    pub fn with_duration(mut self, seconds: f32) -> Self {
+        self.duration = Some(Timer::from_seconds(seconds, TimerMode::Once));
+        self
+    }
+}
+
+/// Base stats plus every modifier stacked on them.
+/// Systems should read final values through `get` rather than keeping their own copies.
+#[derive(Component, Debug, Clone)]
+pub struct CharacterStats {
+    base: [f32; Stat::COUNT],
+    modifiers: Vec<StatModifier>,
+}
+impl Default for CharacterStats {
+    fn default() -> Self {
+        let mut stats = Self {
+            base: [0.0; Stat::COUNT],
+            modifiers: Vec::new(),
+        };
+        stats.set_base(Stat::MaxHealth, 100.0);
+        stats.set_base(Stat::MoveSpeed, 5.0);
+        stats.set_base(Stat::Might, 1.0);
+        stats.set_base(Stat::Area, 1.0);
+        stats.set_base(Stat::Cooldown, 1.0);
+        stats.set_base(Stat::MagnetRadius, 2.0);
+        stats.set_base(Stat::Luck, 1.0);
+        stats
+    }
+}
+impl CharacterStats {
+    pub fn set_base(&mut self, stat: Stat, value: f32) {
+        self.base[stat.index()] = value;
+    }
+
+    /// `(base + every Add) * every Mul`
+    pub fn get(&self, stat: Stat) -> f32 {
+        let (add, mul) = self.modifiers.iter()
+            .filter(|m| m.stat == stat)
+            .fold((0.0, 1.0), |(add, mul), m| match m.op {
+                ModifierOp::Add(v) => (add + v, mul),
+                ModifierOp::Mul(v) => (add, mul * v),
+            });
+        (self.base[stat.index()] + add) * mul
+    }
+
+    /// A timed modifier replaces any other timed one from the same `source` on the same `stat`,
+    /// so a debuff landing again refreshes rather than stacks.
+    pub fn add_modifier(&mut self, modifier: StatModifier) {
+        if modifier.duration.is_some() {
+            self.modifiers.retain(|m| {
+                m.duration.is_none() || m.stat != modifier.stat || m.source != modifier.source
+            });
+        }
+        self.modifiers.push(modifier);
+    }
+}
+
+
+pub struct StatsPlugin;
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Systems
+            .add_systems(FixedUpdate,
+                (expire_stat_modifiers, apply_health_stats)
+                .chain()
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates)
+            )
+        ;
+    }
+}
+
+fn expire_stat_modifiers(
+    time: Res<Time>,
+    mut q_stats: Query<&mut CharacterStats>,
+) {
+    for mut stats in q_stats.iter_mut() {
+        // ticking timers shouldn't count as a change, only actually dropping a modifier should
+        let modifiers = &mut stats.bypass_change_detection().modifiers;
+        let before = modifiers.len();
+        modifiers.retain_mut(|m| match &mut m.duration {
+            Some(timer) => !timer.tick(time.delta()).finished(),
+            None => true,
+        });
+
+        if modifiers.len() != before {
+            stats.set_changed();
+        }
+    }
+}
+
+/// keeps `Health::max` in line with `Stat::MaxHealth`, and handles regeneration.
+pub fn apply_health_stats(
+    time: Res<Time>,
+    mut q_stats: Query<(Ref<CharacterStats>, &mut Health), Without<Dying>>,
+) {
+    for (stats, mut health) in q_stats.iter_mut() {
+        if stats.is_changed() {
+            let max = stats.get(Stat::MaxHealth).max(1.0);
+            // raising max health heals by the same amount, lowering it only clamps
+            let gained = (max - health.max).max(0.0);
+            health.max = max;
+            health.current = (health.current + gained).min(max);
+        }
+
+        let regen = stats.get(Stat::Regeneration);
+        if regen > 0.0 && health.current < health.max {
+            health.current = (health.current + regen * time.delta_seconds()).min(health.max);
+        }
+    }
+}
+
+/// the damage actually taken from a hit of `amount`, once `Stat::Armor` is applied.
+pub fn damage_after_armor(amount: f32, stats: Option<&CharacterStats>) -> f32 {
+    let armor = stats.map_or(0.0, |s| s.get(Stat::Armor));
+    if armor <= 0.0 { return amount; }
+
+    (amount - armor).max(MIN_DAMAGE_AFTER_ARMOR.min(amount))
+}
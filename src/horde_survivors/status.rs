@@ -0,0 +1,280 @@
+use bevy::prelude::*;
+
+use crate::{
+    update_velocity, CharacterStats, DamageEvent, Dying, GameLoopSchedules, GameState, ModifierOp, ModifierSource,
+    Stat, StatModifier, WeaponKind,
+};
+use super::types::AnimationSpeed;
+
+/// seconds between each bit of burn damage
+const BURN_TICK: f32 = 0.5;
+const MAX_BURN_STACKS: usize = 5;
+const MAX_KNOCKBACK_STACKS: usize = 4;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusKind {
+    /// movement and animations slowed by this fraction (0 to 1),
+    /// anything with `CharacterStats` gets a `Stat::MoveSpeed` debuff instead
+    Slow(f32),
+    /// can't move, and animations stop
+    Freeze,
+    /// takes this much damage per second
+    Burn(f32),
+    /// can't move, but still animates
+    Stun,
+    /// pushed along this velocity, easing off to nothing over the effect's duration
+    Knockback(Vec2),
+}
+impl StatusKind {
+    fn stacking(&self) -> Stacking {
+        match self {
+            StatusKind::Slow(_) => Stacking::Strongest,
+            StatusKind::Freeze | StatusKind::Stun => Stacking::Refresh,
+            StatusKind::Burn(_) => Stacking::Stack(MAX_BURN_STACKS),
+            StatusKind::Knockback(_) => Stacking::Stack(MAX_KNOCKBACK_STACKS),
+        }
+    }
+
+    /// compared by `Stacking::Strongest`
+    fn strength(&self) -> f32 {
+        match self {
+            StatusKind::Slow(fraction) => *fraction,
+            StatusKind::Burn(per_second) => *per_second,
+            StatusKind::Knockback(impulse) => impulse.length(),
+            StatusKind::Freeze | StatusKind::Stun => 0.0,
+        }
+    }
+
+    fn is_same_kind(&self, other: &StatusKind) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// How a new effect combines with the ones of the same kind already on an `Entity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stacking {
+    /// only one at a time, whichever has longer left is kept
+    Refresh,
+    /// only one at a time, a stronger one replaces it and an equal one refreshes it
+    Strongest,
+    /// up to this many side by side, past that the one closest to running out is replaced
+    Stack(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    /// seconds
+    pub duration: f32,
+    /// the player's weapon that applied it, so its burn damage is credited to that weapon.
+    pub weapon: Option<WeaponKind>,
+}
+impl StatusEffect {
+    pub fn new(kind: StatusKind, duration: f32) -> Self {
+        Self { kind, duration, weapon: None }
+    }
+
+    pub fn with_weapon(mut self, weapon: WeaponKind) -> Self {
+        self.weapon = Some(weapon);
+        self
+    }
+}
+
+/// Adds a `StatusEffect` to an `Entity`, ignored if it has no `StatusEffects`.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ApplyStatus(pub Entity, pub StatusEffect);
+
+#[derive(Debug)]
+struct ActiveStatus {
+    kind: StatusKind,
+    timer: Timer,
+    weapon: Option<WeaponKind>,
+}
+impl ActiveStatus {
+    fn remaining(&self) -> f32 {
+        self.timer.remaining_secs()
+    }
+}
+
+/// Every status effect currently on an `Entity`. They change how it moves (see `apply_status_effects`)
+/// and animates, until they expire.
+#[derive(Component, Debug)]
+pub struct StatusEffects {
+    active: Vec<ActiveStatus>,
+    burn_tick: Timer,
+}
+impl Default for StatusEffects {
+    fn default() -> Self {
+        Self {
+            active: Vec::new(),
+            burn_tick: Timer::from_seconds(BURN_TICK, TimerMode::Repeating),
+        }
+    }
+}
+impl StatusEffects {
+    pub fn apply(&mut self, effect: StatusEffect) {
+        let new = ActiveStatus {
+            kind: effect.kind,
+            timer: Timer::from_seconds(effect.duration, TimerMode::Once),
+            weapon: effect.weapon,
+        };
+
+        match effect.kind.stacking() {
+            Stacking::Refresh | Stacking::Strongest => {
+                let existing = if let Some(res) = self.active.iter_mut().find(|s| s.kind.is_same_kind(&effect.kind)) {
+                    res
+                } else {
+                    self.active.push(new);
+                    return;
+                };
+
+                let (new_strength, old_strength) = (new.kind.strength(), existing.kind.strength());
+                if new_strength > old_strength {
+                    *existing = new;
+                } else if new_strength == old_strength && new.remaining() > existing.remaining() {
+                    existing.timer = new.timer;
+                }
+            },
+            Stacking::Stack(max_stacks) => {
+                let stacks = self.active.iter().filter(|s| s.kind.is_same_kind(&effect.kind)).count();
+                if stacks < max_stacks {
+                    self.active.push(new);
+                    return;
+                }
+
+                let oldest = self.active.iter_mut()
+                    .filter(|s| s.kind.is_same_kind(&effect.kind))
+                    .min_by(|a, b| a.remaining().total_cmp(&b.remaining()));
+                if let Some(oldest) = oldest {
+                    *oldest = new;
+                }
+            },
+        }
+    }
+
+    fn has(&self, kind: StatusKind) -> bool {
+        self.active.iter().any(|s| s.kind.is_same_kind(&kind))
+    }
+
+    fn slow(&self) -> f32 {
+        self.active.iter()
+            .filter_map(|s| if let StatusKind::Slow(fraction) = s.kind { Some(fraction) } else { None })
+            .fold(0.0, f32::max)
+            .clamp(0.0, 1.0)
+    }
+
+    /// frozen or stunned, it can't move or attack at all.
+    pub fn is_incapacitated(&self) -> bool {
+        self.has(StatusKind::Freeze) || self.has(StatusKind::Stun)
+    }
+
+    /// how much of its own `Velocity` the `Entity` gets to use.
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.is_incapacitated() { return 0.0; }
+        1.0 - self.slow()
+    }
+
+    pub fn animation_speed(&self) -> f32 {
+        if self.has(StatusKind::Freeze) { return 0.0; }
+        1.0 - self.slow()
+    }
+
+    /// every knockback impulse, each fading out as it runs down.
+    pub fn knockback(&self) -> Vec2 {
+        self.active.iter()
+            .filter_map(|s| if let StatusKind::Knockback(impulse) = s.kind {
+                Some(impulse * s.timer.fraction_remaining())
+            } else { None })
+            .sum()
+    }
+
+    /// damage per second from every burn, split up by the weapon that applied it.
+    fn burns_per_second(&self) -> Vec<(Option<WeaponKind>, f32)> {
+        let mut burns: Vec<(Option<WeaponKind>, f32)> = Vec::new();
+        for status in self.active.iter() {
+            let StatusKind::Burn(per_second) = status.kind else { continue; };
+            match burns.iter_mut().find(|(weapon, _)| *weapon == status.weapon) {
+                Some((_, total)) => *total += per_second,
+                None => burns.push((status.weapon, per_second)),
+            }
+        }
+        burns
+    }
+
+    /// the velocity the `Entity` actually moves at, given the one it wants.
+    pub fn modify_velocity(&self, velocity: Vec3) -> Vec3 {
+        velocity * self.speed_multiplier() + self.knockback().extend(0.0)
+    }
+}
+
+
+pub struct StatusPlugin;
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Events
+            .add_event::<ApplyStatus>()
+
+            // Systems
+            .add_systems(FixedUpdate,
+                (apply_status_events, tick_status_effects)
+                .chain()
+                .before(update_velocity)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates)
+            )
+        ;
+    }
+}
+
+fn apply_status_events(
+    mut events: EventReader<ApplyStatus>,
+    mut q_effects: Query<(&mut StatusEffects, Option<&mut CharacterStats>), Without<Dying>>,
+) {
+    for event in events.read() {
+        let Ok((mut effects, stats)) = q_effects.get_mut(event.0) else { continue; };
+
+        match (event.1.kind, stats) {
+            // anything with stats is slowed through `Stat::MoveSpeed`, like any other debuff
+            (StatusKind::Slow(fraction), Some(mut stats)) => {
+                let op = ModifierOp::Mul(1.0 - fraction.clamp(0.0, 1.0));
+                stats.add_modifier(
+                    StatModifier::new(Stat::MoveSpeed, op, ModifierSource::Debuff).with_duration(event.1.duration)
+                );
+            },
+            _ => effects.apply(event.1),
+        }
+    }
+}
+
+pub fn tick_status_effects(
+    time: Res<Time>,
+    mut q_effects: Query<(Entity, &mut StatusEffects, &mut AnimationSpeed, Has<Dying>)>,
+    mut damage: EventWriter<DamageEvent>,
+) {
+    for (entity, mut effects, mut animation_speed, dying) in q_effects.iter_mut() {
+        // nothing should keep a death animation from playing out
+        if dying {
+            effects.active.clear();
+        }
+
+        effects.active.retain_mut(|s| !s.timer.tick(time.delta()).finished());
+
+        let burns = effects.burns_per_second();
+        if !burns.is_empty() {
+            if effects.burn_tick.tick(time.delta()).just_finished() {
+                for (weapon, per_second) in burns {
+                    damage.send(DamageEvent { target: entity, amount: per_second * BURN_TICK, source: None, weapon });
+                }
+            }
+        } else {
+            effects.burn_tick.reset();
+        }
+
+        let speed = effects.animation_speed();
+        if animation_speed.0 != speed {
+            animation_speed.0 = speed;
+        }
+    }
+}
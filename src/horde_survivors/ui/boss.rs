@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+
+use crate::{Boss, BossIncoming, Dying, GameState, Health, RunScoped};
+
+const BOSS_BAR_WIDTH: f32 = 50.0;
+const BOSS_BAR_HEIGHT: f32 = 16.0;
+const BOSS_BAR_COLOR: Color = Color::rgb(0.8, 0.15, 0.25);
+const ANNOUNCEMENT_TIME: f32 = 4.0;
+
+#[derive(Component, Debug, Default)]
+struct BossBar;
+
+#[derive(Component, Debug, Default)]
+struct BossBarFill;
+
+#[derive(Component, Debug, Default)]
+struct BossName;
+
+#[derive(Component, Debug)]
+struct Announcement(Timer);
+
+pub struct BossUiPlugin;
+impl Plugin for BossUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(GameState::Initialize), setup_boss_bar)
+            .add_systems(Update, (
+                    update_boss_bar,
+                    announce_bosses,
+                    hide_announcements,
+                )
+                .run_if(in_state(GameState::Playing)));
+    }
+}
+
+fn setup_boss_bar(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                top: Val::Px(32.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                display: Display::None,
+                ..default()
+            },
+            ..default()
+        },
+        BossBar,
+        RunScoped,
+    )).with_children(|parent| {
+        // ===== Name =====
+        parent.spawn((
+            TextBundle::from_section("", TextStyle { font_size: 22.0, ..default() }),
+            BossName,
+        ));
+
+        // ===== Health Bar =====
+        parent.spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(BOSS_BAR_WIDTH),
+                height: Val::Px(BOSS_BAR_HEIGHT),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::BLACK.with_a(0.6)),
+            border_color: BorderColor(Color::WHITE),
+            ..default()
+        }).with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(BOSS_BAR_COLOR),
+                    ..default()
+                },
+                BossBarFill,
+            ));
+        });
+    });
+}
+
+/// shows the bar while a boss is alive, tracking the first one found.
+fn update_boss_bar(
+    q_bosses: Query<(&Boss, &Health), Without<Dying>>,
+    mut q_bar: Query<&mut Style, (With<BossBar>, Without<BossBarFill>)>,
+    mut q_fill: Query<&mut Style, (With<BossBarFill>, Without<BossBar>)>,
+    mut q_name: Query<&mut Text, With<BossName>>,
+) {
+    let mut bar = if let Ok(res) = q_bar.get_single_mut() {
+        res
+    } else { return; };
+
+    let (boss, health) = if let Some(res) = q_bosses.iter().next() {
+        res
+    } else {
+        if bar.display != Display::None {
+            bar.display = Display::None;
+        }
+        return;
+    };
+
+    if bar.display != Display::Flex {
+        bar.display = Display::Flex;
+    }
+    if let Ok(mut fill) = q_fill.get_single_mut() {
+        fill.width = Val::Percent(health.current / health.max * 100.0);
+    }
+    if let Ok(mut text) = q_name.get_single_mut() {
+        if text.sections[0].value != boss.name {
+            text.sections[0].value = boss.name.clone();
+        }
+    }
+}
+
+fn announce_bosses(
+    mut commands: Commands,
+    mut events: EventReader<BossIncoming>,
+) {
+    for event in events.read() {
+        commands.spawn((
+            TextBundle::from_section(
+                format!("{} approaches!", event.0),
+                TextStyle { font_size: 48.0, color: BOSS_BAR_COLOR, ..default() },
+            ).with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(30.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            }).with_text_justify(JustifyText::Center),
+            Announcement(Timer::from_seconds(ANNOUNCEMENT_TIME, TimerMode::Once)),
+            RunScoped,
+        ));
+    }
+}
+
+fn hide_announcements(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_announcements: Query<(Entity, &mut Announcement)>,
+) {
+    for (entity, mut announcement) in q_announcements.iter_mut() {
+        if announcement.0.tick(time.delta()).just_finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
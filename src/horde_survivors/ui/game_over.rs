@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+
+use crate::{Action, ActionState, GameState, RunStats};
+use super::menu::*;
+use super::style::*;
+
+#[derive(Component, Debug, Default)]
+struct GameOverMenu;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum GameOverButton {
+    Retry,
+    Quit,
+}
+
+pub struct GameOverMenuPlugin;
+impl Plugin for GameOverMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::GameOverMenu), show_game_over_menu)
+            .add_systems(Update, game_over_actions
+                .run_if(in_state(GameState::GameOverMenu)))
+            .add_systems(OnExit(GameState::GameOverMenu), hide_game_over_menu);
+    }
+}
+
+fn show_game_over_menu(
+    mut commands: Commands,
+    stats: Res<RunStats>,
+    mut focus: ResMut<MenuFocus>,
+) {
+    focus.0 = 0;
+
+    let minutes = (stats.time / 60.0) as u32;
+    let seconds = stats.time as u32 % 60;
+
+    // most first
+    let mut kills: Vec<_> = stats.kills.iter().collect();
+    kills.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let mut damage: Vec<_> = stats.weapon_damage.iter().collect();
+    damage.sort_by(|a, b| b.1.total_cmp(a.1));
+
+    commands.spawn((
+        NodeBundle {
+            style: MAIN_WINDOW_BG_STYLE,
+            background_color: MAIN_WINDOW_BG_COLOR,
+            ..default()
+        },
+        GameOverMenu,
+    )).with_children(|parent| {
+        parent.spawn(NodeBundle {
+            style: MENU_PANEL_STYLE,
+            background_color: MENU_PANEL_COLOR,
+            ..default()
+        }).with_children(|parent| {
+            // ===== Title =====
+            parent.spawn(menu_text("Game Over", 68.0));
+            parent.spawn(menu_text(&format!("Survived {:02}:{:02}  |  Level {}", minutes, seconds, stats.level), 32.0));
+
+            // ===== Kills =====
+            parent.spawn(menu_text(&format!("Kills: {}", stats.total_kills()), 28.0));
+            for (name, count) in kills {
+                parent.spawn(menu_text(&format!("{}: {}", name, count), 20.0));
+            }
+
+            // ===== Damage =====
+            parent.spawn(menu_text("Damage", 28.0));
+            for (weapon, amount) in damage {
+                parent.spawn(menu_text(&format!("{}: {:.0}", weapon.name(), amount), 20.0));
+            }
+
+            // ===== Actions =====
+            for (n, (button, label)) in [(GameOverButton::Retry, "Retry"), (GameOverButton::Quit, "Quit to Menu")].into_iter().enumerate() {
+                parent.spawn((
+                    menu_item(n),
+                    button,
+                )).with_children(|parent| {
+                    parent.spawn(menu_text(label, 32.0));
+                });
+            }
+        });
+    });
+}
+
+fn game_over_actions(
+    actions: Res<ActionState>,
+    mut activated: EventReader<MenuItemActivated>,
+    q_buttons: Query<&GameOverButton>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let chosen = activated.read()
+        .find_map(|event| q_buttons.get(event.0).ok().copied());
+    let pressed = if actions.just_pressed(Action::Cancel) {
+        Some(GameOverButton::Quit)
+    } else { None };
+
+    match chosen.or(pressed) {
+        Some(GameOverButton::Retry) => {
+            next_state.set(GameState::Initialize);
+            info!("set 'initialize' game state");
+        },
+        Some(GameOverButton::Quit) => {
+            next_state.set(GameState::MainMenu);
+            info!("set 'main menu' game state");
+        },
+        None => (),
+    }
+}
+
+fn hide_game_over_menu(
+    mut commands: Commands,
+    q_menu: Query<Entity, With<GameOverMenu>>,
+) {
+    for menu_id in q_menu.iter() {
+        commands.entity(menu_id).despawn_recursive();
+    }
+}
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use crate::{GameState, PlayerComponent, PlayerExperience, RunScoped};
+
+const XP_BAR_HEIGHT: f32 = 14.0;
+const XP_BAR_COLOR: Color = Color::rgb(0.25, 0.9, 0.8);
+
+#[derive(Component, Debug, Default)]
+struct Hud;
+
+#[derive(Component, Debug, Default)]
+struct ExperienceBar;
+
+#[derive(Component, Debug, Default)]
+struct LevelText;
+
+pub struct HudPlugin;
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(GameState::Initialize), setup_hud)
+            .add_systems(Update, update_experience_bar);
+    }
+}
+
+fn setup_hud(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Px(XP_BAR_HEIGHT),
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::BLACK.with_a(0.6)),
+            border_color: BorderColor(Color::WHITE),
+            ..default()
+        },
+        Hud,
+        RunScoped,
+    )).with_children(|parent| {
+        // ===== XP Bar =====
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(XP_BAR_COLOR),
+                ..default()
+            },
+            ExperienceBar,
+        ));
+
+        // ===== Level =====
+        parent.spawn((
+            TextBundle::from_section("Lv 1", TextStyle { font_size: 18.0, ..default() })
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(8.0),
+                    top: Val::Px(XP_BAR_HEIGHT),
+                    ..default()
+                }),
+            LevelText,
+        ));
+    });
+}
+
+fn update_experience_bar(
+    q_player: Query<&PlayerExperience, (With<PlayerComponent>, Changed<PlayerExperience>)>,
+    mut q_bar: Query<&mut Style, With<ExperienceBar>>,
+    mut q_text: Query<&mut Text, With<LevelText>>,
+) {
+    let experience = if let Ok(xp) = q_player.get_single() {
+        xp
+    } else { return; };
+
+    if let Ok(mut bar) = q_bar.get_single_mut() {
+        let fill = experience.current as f32 / experience.to_next_level.max(1) as f32;
+        bar.width = Val::Percent(fill * 100.0);
+    }
+    if let Ok(mut text) = q_text.get_single_mut() {
+        text.sections[0].value = format!("Lv {}", experience.level);
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use crate::{GameState, UpgradeChoices, UpgradeChosen};
+use super::menu::*;
+use super::style::*;
+
+#[derive(Component, Debug, Default)]
+struct LevelUpMenu;
+
+/// which of the `UpgradeChoices` this button picks.
+#[derive(Component, Debug)]
+struct UpgradeButton(usize);
+
+const CHOICE_KEYS: [KeyCode; 3] = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3];
+
+pub struct LevelUpMenuPlugin;
+impl Plugin for LevelUpMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+                    rebuild_level_up_menu,
+                    pick_upgrade,
+                )
+                .chain()
+                .run_if(in_state(GameState::LevelUp)))
+            .add_systems(OnExit(GameState::LevelUp), hide_level_up_menu);
+    }
+}
+
+/// (re)builds the menu whenever a new set of choices is rolled.
+fn rebuild_level_up_menu(
+    mut commands: Commands,
+    choices: Res<UpgradeChoices>,
+    mut focus: ResMut<MenuFocus>,
+    q_menu: Query<Entity, With<LevelUpMenu>>,
+) {
+    if !choices.is_changed() { return; }
+
+    for menu_id in q_menu.iter() {
+        commands.entity(menu_id).despawn_recursive();
+    }
+    focus.0 = 0;
+
+    commands.spawn((
+        NodeBundle {
+            style: MAIN_WINDOW_BG_STYLE,
+            background_color: MAIN_WINDOW_BG_COLOR,
+            ..default()
+        },
+        LevelUpMenu,
+    )).with_children(|parent| {
+        parent.spawn(NodeBundle {
+            style: MENU_PANEL_STYLE,
+            background_color: MENU_PANEL_COLOR,
+            ..default()
+        }).with_children(|parent| {
+            // ===== Title =====
+            parent.spawn(menu_text("Level Up!", 68.0));
+
+            // ===== Choices =====
+            for (n, upgrade) in choices.0.iter().enumerate() {
+                parent.spawn((
+                    menu_item(n),
+                    UpgradeButton(n),
+                )).with_children(|parent| {
+                    parent.spawn(menu_text(&format!("{}. {}", n + 1, upgrade.description()), 28.0));
+                });
+            }
+        });
+    });
+}
+
+fn pick_upgrade(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    choices: Res<UpgradeChoices>,
+    mut activated: EventReader<MenuItemActivated>,
+    q_buttons: Query<&UpgradeButton>,
+    mut events: EventWriter<UpgradeChosen>,
+) {
+    let chosen = activated.read()
+        .find_map(|event| q_buttons.get(event.0).ok().map(|button| button.0));
+    let pressed = CHOICE_KEYS.iter()
+        .position(|key| keyboard_input.just_pressed(*key));
+
+    if let Some(upgrade) = chosen.or(pressed).and_then(|n| choices.0.get(n)) {
+        events.send(UpgradeChosen(*upgrade));
+    }
+}
+
+fn hide_level_up_menu(
+    mut commands: Commands,
+    q_menu: Query<Entity, With<LevelUpMenu>>,
+) {
+    for menu_id in q_menu.iter() {
+        commands.entity(menu_id).despawn_recursive();
+    }
+}
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 
 use crate::GameState;
-use super::{style::*, types::LoadingUpdate};
+use super::{main_menu::MainMenuPlugin, menu::MenuNavigationPlugin, pause::PauseMenuPlugin, style::*, types::LoadingUpdate};
 
 #[derive(Component, Debug, Default)]
 struct LoadingMenu;
@@ -12,10 +12,12 @@ struct LoadingBar;
 pub struct UIPlugin;
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup_loading_ui)
+        app.add_plugins((MenuNavigationPlugin, MainMenuPlugin, PauseMenuPlugin))
+            .add_systems(Startup, setup_loading_ui)
             .add_systems(Update, update_loading_ui
                 .run_if(in_state(GameState::Loading)))
-            .add_systems(OnExit(GameState::Loading), hide_loading_ui);
+            .add_systems(OnExit(GameState::Loading), hide_loading_ui)
+            .add_systems(Update, highlight_buttons);
     }
 }
 
@@ -0,0 +1,179 @@
+use bevy::{
+    app::AppExit,
+    prelude::*,
+};
+
+use crate::{Action, ActionState, GameState};
+use super::menu::*;
+use super::options::*;
+use super::style::*;
+
+const CREDITS: &str = include_str!("../../../assets/Credits.txt");
+
+#[derive(Component, Debug, Default)]
+struct MainMenu;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum MainMenuButton {
+    StartRun,
+    Options,
+    Credits,
+    Quit,
+    Option(OptionsButton),
+    Back,
+}
+
+/// Which page of the main menu is showing, it's rebuilt whenever this changes.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum MainMenuPage {
+    #[default]
+    Main,
+    Options(OptionsPage),
+    Credits,
+}
+impl MainMenuPage {
+    /// where `Back` (or `Cancel`) goes from here
+    fn back(&self) -> Self {
+        match self {
+            MainMenuPage::Options(page) => page.back().map_or(MainMenuPage::Main, MainMenuPage::Options),
+            _ => MainMenuPage::Main,
+        }
+    }
+}
+
+pub struct MainMenuPlugin;
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MainMenuPage::default())
+            .add_systems(OnEnter(GameState::MainMenu), open_main_menu)
+            .add_systems(Update, (
+                    rebuild_main_menu,
+                    main_menu_actions,
+                )
+                .chain()
+                .run_if(in_state(GameState::MainMenu)))
+            .add_systems(OnExit(GameState::MainMenu), hide_main_menu);
+    }
+}
+
+fn open_main_menu(mut page: ResMut<MainMenuPage>) {
+    // always marks it changed, so the menu gets built
+    *page = MainMenuPage::Main;
+}
+
+fn rebuild_main_menu(
+    mut commands: Commands,
+    page: Res<MainMenuPage>,
+    options: OptionsView,
+    mut focus: ResMut<MenuFocus>,
+    q_menu: Query<Entity, With<MainMenu>>,
+) {
+    let controls_changed = *page == MainMenuPage::Options(OptionsPage::Controls) && options.controls_changed();
+    if !page.is_changed() && !controls_changed { return; }
+
+    for menu_id in q_menu.iter() {
+        commands.entity(menu_id).despawn_recursive();
+    }
+    if page.is_changed() {
+        focus.0 = 0;
+    }
+
+    commands.spawn((
+        NodeBundle {
+            style: MAIN_WINDOW_BG_STYLE,
+            background_color: MAIN_WINDOW_BG_COLOR,
+            ..default()
+        },
+        MainMenu,
+    )).with_children(|parent| {
+        parent.spawn(NodeBundle {
+            style: MENU_PANEL_STYLE,
+            background_color: MENU_PANEL_COLOR,
+            ..default()
+        }).with_children(|parent| {
+            let items: Vec<(MainMenuButton, String)> = match *page {
+                MainMenuPage::Main => {
+                    parent.spawn(menu_text("Horde Survivor", 68.0));
+                    vec![
+                        (MainMenuButton::StartRun, "Start Run".into()),
+                        (MainMenuButton::Options, "Options".into()),
+                        (MainMenuButton::Credits, "Credits".into()),
+                        (MainMenuButton::Quit, "Quit".into()),
+                    ]
+                },
+                MainMenuPage::Options(options_page) => {
+                    options.spawn_page(parent, options_page).into_iter()
+                        .map(|(button, label)| (MainMenuButton::Option(button), label))
+                        .collect()
+                },
+                MainMenuPage::Credits => {
+                    parent.spawn(menu_text("Credits", 68.0));
+                    for line in CREDITS.lines().filter(|line| !line.trim().is_empty()) {
+                        parent.spawn(menu_text(line, 20.0));
+                    }
+                    vec![(MainMenuButton::Back, "Back".into())]
+                },
+            };
+
+            for (n, (button, label)) in items.into_iter().enumerate() {
+                parent.spawn((
+                    menu_item(n),
+                    button,
+                )).with_children(|parent| {
+                    parent.spawn(menu_text(&label, 32.0));
+                });
+            }
+        });
+    });
+}
+
+fn main_menu_actions(
+    actions: Res<ActionState>,
+    mut activated: EventReader<MenuItemActivated>,
+    q_buttons: Query<&MainMenuButton>,
+    mut page: ResMut<MainMenuPage>,
+    mut options: OptionsControls,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    if actions.just_pressed(Action::Cancel) && *page != MainMenuPage::Main {
+        *page = page.back();
+        return;
+    }
+
+    for event in activated.read() {
+        let button = if let Ok(button) = q_buttons.get(event.0) {
+            *button
+        } else { continue; };
+
+        match button {
+            MainMenuButton::StartRun => {
+                next_state.set(GameState::Initialize);
+                info!("set 'initialize' game state");
+            },
+            MainMenuButton::Options => *page = MainMenuPage::Options(OptionsPage::Options),
+            MainMenuButton::Credits => *page = MainMenuPage::Credits,
+            MainMenuButton::Quit => {
+                app_exit.send(AppExit);
+            },
+            MainMenuButton::Option(button) => {
+                let MainMenuPage::Options(options_page) = *page else { continue; };
+                match options.press(options_page, button) {
+                    OptionsNav::Stay => (),
+                    OptionsNav::Show(options_page) => *page = MainMenuPage::Options(options_page),
+                    OptionsNav::Leave => *page = MainMenuPage::Main,
+                }
+            },
+            MainMenuButton::Back => *page = page.back(),
+        }
+    }
+}
+
+fn hide_main_menu(
+    mut commands: Commands,
+    q_menu: Query<Entity, With<MainMenu>>,
+) {
+    for menu_id in q_menu.iter() {
+        commands.entity(menu_id).despawn_recursive();
+    }
+}
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use crate::{Action, ActionState};
+use super::style::*;
+
+/// A `Button` in a menu that can be navigated with the move and `Confirm` actions, as well as the mouse.
+/// `index` is its place in the up / down order.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MenuItem {
+    pub index: usize,
+}
+
+/// Which `MenuItem` is selected, menus reset it whenever they're (re)built.
+#[derive(Resource, Debug, Default)]
+pub struct MenuFocus(pub usize);
+
+/// Sent when a `MenuItem` is clicked, or `Confirm`ed.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct MenuItemActivated(pub Entity);
+
+pub struct MenuNavigationPlugin;
+impl Plugin for MenuNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Resources
+            .insert_resource(MenuFocus::default())
+
+            // Events
+            .add_event::<MenuItemActivated>()
+
+            // Systems
+            .add_systems(Update, (navigate_menu, highlight_menu_items).chain());
+    }
+}
+
+/// A `menu_button` that's part of the keyboard navigation.
+pub fn menu_item(index: usize) -> (ButtonBundle, MenuItem) {
+    (menu_button(), MenuItem { index })
+}
+
+fn navigate_menu(
+    actions: Res<ActionState>,
+    mut focus: ResMut<MenuFocus>,
+    q_items: Query<(Entity, &MenuItem, &Interaction)>,
+    q_changed: Query<(Entity, &MenuItem, &Interaction), Changed<Interaction>>,
+    mut activated: EventWriter<MenuItemActivated>,
+) {
+    let count = q_items.iter().count();
+    if count == 0 { return; }
+
+    // the mouse moves the focus too, so the highlight is never in two places
+    for (entity, item, interaction) in q_changed.iter() {
+        match interaction {
+            Interaction::Hovered => focus.0 = item.index,
+            Interaction::Pressed => {
+                focus.0 = item.index;
+                activated.send(MenuItemActivated(entity));
+            },
+            Interaction::None => (),
+        }
+    }
+
+    if actions.just_pressed(Action::MoveUp) {
+        focus.0 = (focus.0 + count - 1) % count;
+    }
+    if actions.just_pressed(Action::MoveDown) {
+        focus.0 = (focus.0 + 1) % count;
+    }
+    // wherever it was, it has to land on something in this menu
+    if focus.0 >= count {
+        focus.0 = 0;
+    }
+
+    if actions.just_pressed(Action::Confirm) {
+        if let Some((entity, _, _)) = q_items.iter().find(|(_, item, _)| item.index == focus.0) {
+            activated.send(MenuItemActivated(entity));
+        }
+    }
+}
+
+fn highlight_menu_items(
+    focus: Res<MenuFocus>,
+    mut q_items: Query<(&MenuItem, &Interaction, &mut BackgroundColor)>,
+) {
+    for (item, interaction, mut color) in q_items.iter_mut() {
+        let next = if *interaction == Interaction::Pressed {
+            MENU_BUTTON_PRESSED_COLOR
+        } else if item.index == focus.0 {
+            MENU_BUTTON_HOVERED_COLOR
+        } else {
+            MENU_BUTTON_COLOR
+        };
+
+        if color.0 != next.0 {
+            *color = next;
+        }
+    }
+}
@@ -1,5 +1,13 @@
 
 pub mod loading;
+pub mod level_up;
+pub mod hud;
+pub mod boss;
+pub mod game_over;
+pub mod main_menu;
+pub mod menu;
+pub mod options;
+pub mod pause;
 mod style;
 
 pub(super) use crate::horde_survivors::*;
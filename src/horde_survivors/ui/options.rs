@@ -0,0 +1,152 @@
+//! The options pages, shared by the main and pause menus.
+//! Each menu shows them as one of its own pages, and passes their buttons on to `OptionsControls::press`.
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    window::{PrimaryWindow, WindowMode},
+};
+
+use crate::{Action, InputBindings, PendingRebind};
+use super::style::*;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OptionsPage {
+    #[default]
+    Options,
+    Controls,
+}
+impl OptionsPage {
+    /// where `Back` (or `Cancel`) goes from here, `None` leaves the options.
+    pub fn back(&self) -> Option<Self> {
+        match self {
+            OptionsPage::Controls => Some(OptionsPage::Options),
+            OptionsPage::Options => None,
+        }
+    }
+}
+
+/// A button on one of the options pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionsButton {
+    ToggleFullscreen,
+    Controls,
+    Rebind(Action),
+    ResetDefaults,
+    Back,
+}
+
+/// Where the menu showing the options should go, once a button's been pressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionsNav {
+    /// the page stays as it is
+    Stay,
+    /// (re)builds this page
+    Show(OptionsPage),
+    /// back out of the options, to the menu's own page
+    Leave,
+}
+
+/// What the options pages show, for a menu's rebuild system.
+#[derive(SystemParam)]
+pub struct OptionsView<'w, 's> {
+    bindings: Res<'w, InputBindings>,
+    pending: Res<'w, PendingRebind>,
+    q_window: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+}
+impl OptionsView<'_, '_> {
+    /// the controls page is refreshed (in place) whenever a key is rebound.
+    pub fn controls_changed(&self) -> bool {
+        self.bindings.is_changed() || self.pending.is_changed()
+    }
+
+    /// Spawns `page`'s title, and returns its buttons and their labels in order.
+    pub fn spawn_page(&self, parent: &mut ChildBuilder, page: OptionsPage) -> Vec<(OptionsButton, String)> {
+        match page {
+            OptionsPage::Options => {
+                parent.spawn(menu_text("Options", 68.0));
+                vec![
+                    (OptionsButton::ToggleFullscreen, fullscreen_label(self.q_window.get_single().ok())),
+                    (OptionsButton::Controls, "Controls".into()),
+                    (OptionsButton::Back, "Back".into()),
+                ]
+            },
+            OptionsPage::Controls => {
+                parent.spawn(menu_text("Controls", 68.0));
+                let mut items: Vec<_> = Action::REBINDABLE.into_iter()
+                    .map(|action| {
+                        let keys = if self.pending.0 == Some(action) {
+                            "press a key...".to_string()
+                        } else {
+                            self.bindings.keys(action).map(key_name).collect::<Vec<_>>().join(", ")
+                        };
+                        (OptionsButton::Rebind(action), format!("{}: {}", action.name(), keys))
+                    })
+                    .collect();
+                items.push((OptionsButton::ResetDefaults, "Reset to Defaults".into()));
+                items.push((OptionsButton::Back, "Back".into()));
+                items
+            },
+        }
+    }
+}
+
+/// Changes the options, for a menu's actions system.
+#[derive(SystemParam)]
+pub struct OptionsControls<'w, 's> {
+    bindings: ResMut<'w, InputBindings>,
+    pending: ResMut<'w, PendingRebind>,
+    q_window: Query<'w, 's, &'static mut Window, With<PrimaryWindow>>,
+}
+impl OptionsControls<'_, '_> {
+    pub fn press(&mut self, page: OptionsPage, button: OptionsButton) -> OptionsNav {
+        let nav = match button {
+            OptionsButton::ToggleFullscreen => {
+                if let Ok(mut window) = self.q_window.get_single_mut() {
+                    toggle_fullscreen(&mut window);
+                }
+                // rebuilt, for the new label
+                OptionsNav::Show(page)
+            },
+            OptionsButton::Controls => OptionsNav::Show(OptionsPage::Controls),
+            OptionsButton::Rebind(action) => {
+                self.pending.0 = Some(action);
+                OptionsNav::Stay
+            },
+            OptionsButton::ResetDefaults => {
+                *self.bindings = InputBindings::default();
+                self.bindings.save();
+                OptionsNav::Stay
+            },
+            OptionsButton::Back => page.back().map_or(OptionsNav::Leave, OptionsNav::Show),
+        };
+
+        // left the controls before pressing a key
+        if nav != OptionsNav::Stay && self.pending.0.is_some() {
+            self.pending.0 = None;
+        }
+        nav
+    }
+}
+
+fn fullscreen_label(window: Option<&Window>) -> String {
+    let fullscreen = window.is_some_and(|w| w.mode != WindowMode::Windowed);
+    format!("Fullscreen: {}", if fullscreen { "On" } else { "Off" })
+}
+
+fn toggle_fullscreen(window: &mut Window) {
+    window.mode = if window.mode == WindowMode::Windowed {
+        WindowMode::BorderlessFullscreen
+    } else {
+        WindowMode::Windowed
+    };
+}
+
+/// `KeyW` => `W`, `Digit1` => `1` etc.
+fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+
+use crate::{Action, ActionState, GameState};
+use super::menu::*;
+use super::options::*;
+use super::style::*;
+
+#[derive(Component, Debug, Default)]
+struct PauseMenu;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum PauseMenuButton {
+    Resume,
+    Restart,
+    Options,
+    Quit,
+    Option(OptionsButton),
+}
+
+/// Which page of the pause menu is showing, it's rebuilt whenever this changes.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum PauseMenuPage {
+    #[default]
+    Main,
+    Options(OptionsPage),
+}
+
+pub struct PauseMenuPlugin;
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PauseMenuPage::default())
+            .add_systems(OnEnter(GameState::PauseMenu), open_pause_menu)
+            .add_systems(Update, (
+                    rebuild_pause_menu,
+                    pause_menu_actions,
+                )
+                .chain()
+                .run_if(in_state(GameState::PauseMenu)))
+            .add_systems(OnExit(GameState::PauseMenu), hide_pause_menu);
+    }
+}
+
+fn open_pause_menu(mut page: ResMut<PauseMenuPage>) {
+    // always marks it changed, so the menu gets built
+    *page = PauseMenuPage::Main;
+}
+
+fn rebuild_pause_menu(
+    mut commands: Commands,
+    page: Res<PauseMenuPage>,
+    options: OptionsView,
+    mut focus: ResMut<MenuFocus>,
+    q_menu: Query<Entity, With<PauseMenu>>,
+) {
+    let controls_changed = *page == PauseMenuPage::Options(OptionsPage::Controls) && options.controls_changed();
+    if !page.is_changed() && !controls_changed { return; }
+
+    for menu_id in q_menu.iter() {
+        commands.entity(menu_id).despawn_recursive();
+    }
+    if page.is_changed() {
+        focus.0 = 0;
+    }
+
+    commands.spawn((
+        NodeBundle {
+            style: MAIN_WINDOW_BG_STYLE,
+            background_color: OVERLAY_BG_COLOR,
+            ..default()
+        },
+        PauseMenu,
+    )).with_children(|parent| {
+        parent.spawn(NodeBundle {
+            style: MENU_PANEL_STYLE,
+            background_color: MENU_PANEL_COLOR,
+            ..default()
+        }).with_children(|parent| {
+            let items: Vec<(PauseMenuButton, String)> = match *page {
+                PauseMenuPage::Main => {
+                    parent.spawn(menu_text("Paused", 68.0));
+                    vec![
+                        (PauseMenuButton::Resume, "Resume".into()),
+                        (PauseMenuButton::Restart, "Restart".into()),
+                        (PauseMenuButton::Options, "Options".into()),
+                        (PauseMenuButton::Quit, "Quit to Menu".into()),
+                    ]
+                },
+                PauseMenuPage::Options(options_page) => {
+                    options.spawn_page(parent, options_page).into_iter()
+                        .map(|(button, label)| (PauseMenuButton::Option(button), label))
+                        .collect()
+                },
+            };
+
+            for (n, (button, label)) in items.into_iter().enumerate() {
+                parent.spawn((
+                    menu_item(n),
+                    button,
+                )).with_children(|parent| {
+                    parent.spawn(menu_text(&label, 32.0));
+                });
+            }
+        });
+    });
+}
+
+fn pause_menu_actions(
+    actions: Res<ActionState>,
+    mut activated: EventReader<MenuItemActivated>,
+    q_buttons: Query<&PauseMenuButton>,
+    mut page: ResMut<PauseMenuPage>,
+    mut options: OptionsControls,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // cancel backs out of the options, or resumes. pausing again resumes too.
+    let back = (actions.just_pressed(Action::Cancel) || actions.just_pressed(Action::Pause)).then_some(match *page {
+        PauseMenuPage::Main => PauseMenuButton::Resume,
+        PauseMenuPage::Options(_) => PauseMenuButton::Option(OptionsButton::Back),
+    });
+    let chosen = activated.read()
+        .find_map(|event| q_buttons.get(event.0).ok().copied());
+
+    match chosen.or(back) {
+        Some(PauseMenuButton::Resume) => {
+            next_state.set(GameState::Playing);
+            info!("set 'playing' game state");
+        },
+        Some(PauseMenuButton::Restart) => {
+            next_state.set(GameState::Initialize);
+            info!("set 'initialize' game state");
+        },
+        Some(PauseMenuButton::Options) => *page = PauseMenuPage::Options(OptionsPage::Options),
+        Some(PauseMenuButton::Quit) => {
+            next_state.set(GameState::MainMenu);
+            info!("set 'main menu' game state");
+        },
+        Some(PauseMenuButton::Option(button)) => {
+            let PauseMenuPage::Options(options_page) = *page else { return; };
+            match options.press(options_page, button) {
+                OptionsNav::Stay => (),
+                OptionsNav::Show(options_page) => *page = PauseMenuPage::Options(options_page),
+                OptionsNav::Leave => *page = PauseMenuPage::Main,
+            }
+        },
+        None => (),
+    }
+}
+
+fn hide_pause_menu(
+    mut commands: Commands,
+    q_menu: Query<Entity, With<PauseMenu>>,
+) {
+    for menu_id in q_menu.iter() {
+        commands.entity(menu_id).despawn_recursive();
+    }
+}
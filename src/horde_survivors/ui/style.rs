@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use super::menu::MenuItem;
+
 pub const MAIN_WINDOW_BG_STYLE: Style = {
     let mut style = Style::DEFAULT;
     style.width = Val::Percent(100.0);
@@ -9,6 +11,34 @@ pub const MAIN_WINDOW_BG_STYLE: Style = {
     style
 };
 pub const MAIN_WINDOW_BG_COLOR: BackgroundColor = BackgroundColor(Color::RgbaLinear { red: 0., green: 0., blue: 0., alpha: 0.9 });
+/// lighter than `MAIN_WINDOW_BG_COLOR`, so the (frozen) game still shows through
+pub const OVERLAY_BG_COLOR: BackgroundColor = BackgroundColor(Color::RgbaLinear { red: 0., green: 0., blue: 0., alpha: 0.6 });
+
+pub const MENU_PANEL_STYLE: Style = {
+    let mut style = Style::DEFAULT;
+    style.align_self = AlignSelf::Center;
+    style.width = Val::Percent(60.0);
+    style.height = Val::Percent(85.0);
+    style.flex_direction = FlexDirection::Column;
+    style.justify_content = JustifyContent::SpaceEvenly;
+    style.align_content = AlignContent::Center;
+    style
+};
+pub const MENU_PANEL_COLOR: BackgroundColor = BackgroundColor(Color::rgba(0.5, 0.5, 0.5, 0.33));
+
+pub const MENU_BUTTON_STYLE: Style = {
+    let mut style = Style::DEFAULT;
+    style.align_self = AlignSelf::Center;
+    style.width = Val::Percent(60.0);
+    style.height = Val::Px(64.0);
+    style.justify_content = JustifyContent::Center;
+    style.align_items = AlignItems::Center;
+    style.border = UiRect::all(Val::Px(2.0));
+    style
+};
+pub const MENU_BUTTON_COLOR: BackgroundColor = BackgroundColor(Color::rgba(0.15, 0.15, 0.15, 0.9));
+pub const MENU_BUTTON_HOVERED_COLOR: BackgroundColor = BackgroundColor(Color::rgba(0.3, 0.3, 0.3, 0.9));
+pub const MENU_BUTTON_PRESSED_COLOR: BackgroundColor = BackgroundColor(Color::rgba(0.45, 0.45, 0.45, 0.9));
 
 
 pub fn menu_text(text: &str, size: f32) -> TextBundle {
@@ -23,4 +53,26 @@ pub fn menu_text(text: &str, size: f32) -> TextBundle {
         justify_self: JustifySelf::Center,
         ..default()
     })
-}
\ No newline at end of file
+}
+
+pub fn menu_button() -> ButtonBundle {
+    ButtonBundle {
+        style: MENU_BUTTON_STYLE,
+        background_color: MENU_BUTTON_COLOR,
+        border_color: BorderColor(Color::WHITE),
+        ..default()
+    }
+}
+
+/// Highlights any `Button` the mouse is over (or pressing).
+pub fn highlight_buttons(
+    mut q_buttons: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<Button>, Without<MenuItem>)>,
+) {
+    for (interaction, mut color) in q_buttons.iter_mut() {
+        *color = match interaction {
+            Interaction::Pressed => MENU_BUTTON_PRESSED_COLOR,
+            Interaction::Hovered => MENU_BUTTON_HOVERED_COLOR,
+            Interaction::None => MENU_BUTTON_COLOR,
+        };
+    }
+}
@@ -0,0 +1,264 @@
+use bevy::prelude::*;
+
+use crate::{
+    ApplyStatus, CollisionLayers, DamageEvent, OnHitStatus, ProjectileStats, RunScoped, StatusEffect, StatusKind,
+    LAYER_DESTRUCTIBLE, LAYER_ENEMY, LAYER_PROJECTILE,
+};
+use crate::horde_survivors::projectile::spawn_projectile;
+use super::{FireContext, WeaponBehaviour, WeaponEffect, WeaponStats};
+
+// everything the player's weapons can hurt
+const TARGET_LAYERS: u32 = LAYER_ENEMY | LAYER_DESTRUCTIBLE;
+
+const DAGGER_SPEED: f32 = 12.0;
+const DAGGER_LIFETIME: f32 = 1.5;
+const DAGGER_SPREAD: f32 = 0.15;
+
+const BLADE_DURATION: f32 = 3.0;
+const BLADE_ORBIT_SPEED: f32 = 4.0;
+const BLADE_SLOW: f32 = 0.4;
+const BLADE_SLOW_DURATION: f32 = 1.0;
+
+/// fraction of the aura's damage that keeps burning (per second) after each pulse
+const AURA_BURN_FRACTION: f32 = 0.5;
+const AURA_BURN_DURATION: f32 = 2.0;
+
+const SLASH_KNOCKBACK: f32 = 8.0;
+/// long enough for the knockback to play out before enemies start walking back in
+const SLASH_STUN_DURATION: f32 = 0.3;
+
+const PULSE_DURATION: f32 = 0.25;
+const SLASH_DURATION: f32 = 0.15;
+const SLASH_WIDTH: f32 = 0.4;
+
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WeaponKind {
+    Dagger,
+    OrbitingBlades,
+    Aura,
+    MeleeArc,
+}
+impl WeaponKind {
+    pub const ALL: [WeaponKind; 4] = [
+        WeaponKind::Dagger,
+        WeaponKind::OrbitingBlades,
+        WeaponKind::Aura,
+        WeaponKind::MeleeArc,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeaponKind::Dagger => "Throwing Dagger",
+            WeaponKind::OrbitingBlades => "Orbiting Blades",
+            WeaponKind::Aura => "Burning Aura",
+            WeaponKind::MeleeArc => "Sword Slash",
+        }
+    }
+
+    /// level 1 stats, and the behaviour for this kind of weapon.
+    pub(super) fn create(&self) -> (WeaponStats, Box<dyn WeaponBehaviour>) {
+        match self {
+            WeaponKind::Dagger => (
+                WeaponStats { level: 1, cooldown: 1.25, damage: 5.0, area: DAGGER_SPEED * DAGGER_LIFETIME, amount: 1 },
+                Box::new(ThrownDagger { pierce: 1 }),
+            ),
+            WeaponKind::OrbitingBlades => (
+                WeaponStats { level: 1, cooldown: 5.0, damage: 4.0, area: 2.0, amount: 2 },
+                Box::new(OrbitingBlades),
+            ),
+            WeaponKind::Aura => (
+                WeaponStats { level: 1, cooldown: 0.75, damage: 1.5, area: 2.5, amount: 1 },
+                Box::new(DamageAura),
+            ),
+            WeaponKind::MeleeArc => (
+                WeaponStats { level: 1, cooldown: 1.5, damage: 8.0, area: 2.5, amount: 1 },
+                Box::new(MeleeArc { arc: std::f32::consts::FRAC_PI_2 }),
+            ),
+        }
+    }
+}
+
+/// moves an `Entity` in a circle around its `owner`.
+#[derive(Component, Debug)]
+pub struct Orbit {
+    pub owner: Entity,
+    pub angle: f32,
+    pub radius: f32,
+    /// radians per second
+    pub speed: f32,
+}
+
+
+/// Throws `amount` daggers at the nearest target in range (`area`), or straight ahead if there is none.
+#[derive(Debug)]
+struct ThrownDagger {
+    pierce: u32,
+}
+impl WeaponBehaviour for ThrownDagger {
+    fn fire(&mut self, stats: &WeaponStats, ctx: &mut FireContext) {
+        let target = ctx.index.0.nearest(ctx.origin.truncate(), LAYER_ENEMY)
+            .map(|entry| entry.position - ctx.origin.truncate())
+            .filter(|dir| dir.length_squared() <= stats.area * stats.area);
+        let aim = target.unwrap_or(ctx.facing);
+        if aim == Vec2::ZERO { return; }
+
+        let projectile_stats = ProjectileStats {
+            speed: DAGGER_SPEED,
+            lifetime: stats.area / DAGGER_SPEED,
+            pierce: self.pierce,
+            damage: stats.damage,
+        };
+
+        // fan the volley out around the aim direction
+        let first = -DAGGER_SPREAD * stats.amount.saturating_sub(1) as f32 * 0.5;
+        for n in 0..stats.amount {
+            let direction = Vec2::from_angle(first + DAGGER_SPREAD * n as f32).rotate(aim);
+            let dagger = spawn_projectile(
+                ctx.origin,
+                direction,
+                projectile_stats,
+                CollisionLayers::new(LAYER_PROJECTILE, TARGET_LAYERS),
+                ctx.commands,
+                ctx.meshes,
+            );
+            ctx.commands.entity(dagger).insert(WeaponKind::Dagger);
+        }
+    }
+
+    fn level_up(&mut self, stats: &mut WeaponStats) {
+        if stats.level.is_multiple_of(2) { stats.amount += 1; }
+        if stats.level.is_multiple_of(3) { self.pierce += 1; }
+    }
+}
+
+/// Blades that circle the owner at a distance of `area` for a few seconds.
+#[derive(Debug)]
+struct OrbitingBlades;
+impl WeaponBehaviour for OrbitingBlades {
+    fn fire(&mut self, stats: &WeaponStats, ctx: &mut FireContext) {
+        let projectile_stats = ProjectileStats {
+            speed: 0.0,
+            lifetime: BLADE_DURATION,
+            // blades hit everything they pass through
+            pierce: u32::MAX,
+            damage: stats.damage,
+        };
+
+        if stats.amount == 0 { return; }
+
+        let step = std::f32::consts::TAU / stats.amount as f32;
+        for n in 0..stats.amount {
+            let angle = step * n as f32;
+            let blade = spawn_projectile(
+                ctx.origin + (Vec2::from_angle(angle) * stats.area).extend(0.0),
+                Vec2::ZERO,
+                projectile_stats,
+                CollisionLayers::new(LAYER_PROJECTILE, TARGET_LAYERS),
+                ctx.commands,
+                ctx.meshes,
+            );
+            ctx.commands.entity(blade).insert((
+                WeaponKind::OrbitingBlades,
+                OnHitStatus(StatusEffect::new(StatusKind::Slow(BLADE_SLOW), BLADE_SLOW_DURATION)),
+                Orbit {
+                    owner: ctx.owner,
+                    angle,
+                    radius: stats.area,
+                    speed: BLADE_ORBIT_SPEED,
+                },
+            ));
+        }
+    }
+
+    fn level_up(&mut self, stats: &mut WeaponStats) {
+        stats.amount += 1;
+    }
+}
+
+/// Damages everything within `area` of the owner, and sets it burning.
+#[derive(Debug)]
+struct DamageAura;
+impl WeaponBehaviour for DamageAura {
+    fn fire(&mut self, stats: &WeaponStats, ctx: &mut FireContext) {
+        let (owner, damage, statuses) = (ctx.owner, &mut ctx.damage, &mut ctx.statuses);
+        let burn = StatusEffect::new(StatusKind::Burn(stats.damage * AURA_BURN_FRACTION), AURA_BURN_DURATION)
+            .with_weapon(WeaponKind::Aura);
+        ctx.index.0.for_each_in_radius(ctx.origin.truncate(), stats.area, TARGET_LAYERS, |entry| {
+            damage.send(DamageEvent {
+                target: entry.entity,
+                amount: stats.damage,
+                source: Some(owner),
+                weapon: Some(WeaponKind::Aura),
+            });
+            statuses.send(ApplyStatus(entry.entity, burn));
+        });
+
+        ctx.commands.spawn((
+            PbrBundle {
+                mesh: ctx.assets.pulse_mesh.clone(),
+                material: ctx.assets.pulse_material.clone(),
+                transform: Transform::from_translation(ctx.origin + Vec3::Z * 0.05)
+                    .with_scale(Vec3::splat(stats.area)),
+                ..default()
+            },
+            WeaponEffect(Timer::from_seconds(PULSE_DURATION, TimerMode::Once)),
+            RunScoped,
+        ));
+    }
+}
+
+/// Damages everything within `area` of the owner, inside an `arc` (radians) centered on the way they're facing.
+/// Whatever is hit gets knocked back (and briefly stunned).
+#[derive(Debug)]
+struct MeleeArc {
+    arc: f32,
+}
+impl WeaponBehaviour for MeleeArc {
+    fn fire(&mut self, stats: &WeaponStats, ctx: &mut FireContext) {
+        let facing = ctx.facing;
+        if facing == Vec2::ZERO { return; }
+
+        let (owner, origin, half_arc) = (ctx.owner, ctx.origin.truncate(), self.arc * 0.5);
+        let (damage, statuses) = (&mut ctx.damage, &mut ctx.statuses);
+        ctx.index.0.for_each_in_radius(origin, stats.area, TARGET_LAYERS, |entry| {
+            let to_target = entry.position - origin;
+            // right on top of the owner counts as a hit
+            if to_target.length_squared() > f32::EPSILON && facing.angle_between(to_target).abs() > half_arc { return; }
+
+            damage.send(DamageEvent {
+                target: entry.entity,
+                amount: stats.damage,
+                source: Some(owner),
+                weapon: Some(WeaponKind::MeleeArc),
+            });
+
+            let away = to_target.try_normalize().unwrap_or(facing);
+            statuses.send(ApplyStatus(entry.entity, StatusEffect::new(StatusKind::Knockback(away * SLASH_KNOCKBACK), SLASH_STUN_DURATION)));
+            statuses.send(ApplyStatus(entry.entity, StatusEffect::new(StatusKind::Stun, SLASH_STUN_DURATION)));
+        });
+
+        // a flat blade shaped swipe, across the front of the owner
+        let center = origin + facing * stats.area * 0.5;
+        let swipe_width = 2.0 * stats.area * (half_arc.min(std::f32::consts::FRAC_PI_2)).sin();
+        ctx.commands.spawn((
+            PbrBundle {
+                mesh: ctx.assets.slash_mesh.clone(),
+                material: ctx.assets.slash_material.clone(),
+                transform: Transform::from_translation(center.extend(0.05))
+                    .with_rotation(Quat::from_rotation_z(-f32::atan2(facing.x, facing.y)))
+                    .with_scale(Vec3::new(swipe_width, SLASH_WIDTH, 1.0)),
+                ..default()
+            },
+            WeaponEffect(Timer::from_seconds(SLASH_DURATION, TimerMode::Once)),
+            RunScoped,
+        ));
+    }
+
+    fn level_up(&mut self, stats: &mut WeaponStats) {
+        // the swing widens out to a half circle
+        if stats.level.is_multiple_of(2) {
+            self.arc = (self.arc + 0.25).min(std::f32::consts::PI);
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+};
+
+use crate::{
+    ApplyStatus, CharacterStats, DamageEvent, Dying, Stat, GameLoopSchedules, GameState, PlayerComponent, SpatialIndex,
+    push_out_of_obstacles,
+};
+use super::types::SpawnMesh;
+
+mod kinds;
+pub use kinds::*;
+
+const MAX_WEAPON_SLOTS: usize = 4;
+pub const MAX_WEAPON_LEVEL: u32 = 8;
+
+// how much the shared stats improve with each level
+const LEVEL_DAMAGE_MULT: f32 = 1.2;
+const LEVEL_COOLDOWN_MULT: f32 = 0.92;
+const LEVEL_AREA_MULT: f32 = 1.1;
+
+
+/// Stats every weapon has, whatever it does with them.
+#[derive(Debug, Clone, Copy)]
+pub struct WeaponStats {
+    pub level: u32,
+    /// seconds between each `fire`
+    pub cooldown: f32,
+    pub damage: f32,
+    /// range / radius / size, depending on the weapon
+    pub area: f32,
+    /// how many projectiles / blades etc. each `fire` makes, for the weapons that care.
+    pub amount: u32,
+}
+impl WeaponStats {
+    /// the stats the weapon actually fires with, once the owner's `CharacterStats` are applied.
+    fn with_owner_stats(&self, owner: &CharacterStats) -> Self {
+        Self {
+            level: self.level,
+            cooldown: self.cooldown * owner.get(Stat::Cooldown),
+            damage: self.damage * owner.get(Stat::Might),
+            area: self.area * owner.get(Stat::Area),
+            amount: self.amount + owner.get(Stat::ProjectileCount).max(0.0) as u32,
+        }
+    }
+}
+
+/// Everything a `WeaponBehaviour` gets to work with when it fires.
+pub struct FireContext<'a, 'w, 's> {
+    pub owner: Entity,
+    pub origin: Vec3,
+    /// the direction the owner is facing (see `update_facing`), on the XY plane.
+    pub facing: Vec2,
+    pub index: &'a SpatialIndex,
+    pub assets: &'a WeaponAssets,
+    pub commands: &'a mut Commands<'w, 's>,
+    pub meshes: &'a mut EventWriter<'w, SpawnMesh>,
+    pub damage: &'a mut EventWriter<'w, DamageEvent>,
+    pub statuses: &'a mut EventWriter<'w, ApplyStatus>,
+}
+
+/// The params a weapon writes to, grouped so they share the `'w` / `'s` lifetimes `FireContext` needs.
+#[derive(SystemParam)]
+struct WeaponOutput<'w, 's> {
+    commands: Commands<'w, 's>,
+    meshes: EventWriter<'w, SpawnMesh>,
+    damage: EventWriter<'w, DamageEvent>,
+    statuses: EventWriter<'w, ApplyStatus>,
+}
+
+/// What a weapon actually does. Each `WeaponKind` maps to one of these.
+pub trait WeaponBehaviour: Send + Sync + std::fmt::Debug {
+    /// called every time the weapon's cooldown elapses.
+    fn fire(&mut self, stats: &WeaponStats, ctx: &mut FireContext);
+
+    /// called once `stats` have been raised to their new level, for anything the shared multipliers don't cover.
+    fn level_up(&mut self, _stats: &mut WeaponStats) {}
+}
+
+#[derive(Debug)]
+pub struct Weapon {
+    pub kind: WeaponKind,
+    pub stats: WeaponStats,
+    cooldown: Timer,
+    behaviour: Box<dyn WeaponBehaviour>,
+}
+impl Weapon {
+    pub fn new(kind: WeaponKind) -> Self {
+        let (stats, behaviour) = kind.create();
+        Self {
+            kind,
+            stats,
+            cooldown: Timer::from_seconds(stats.cooldown, TimerMode::Repeating),
+            behaviour,
+        }
+    }
+
+    pub fn is_max_level(&self) -> bool {
+        self.stats.level >= MAX_WEAPON_LEVEL
+    }
+
+    pub fn level_up(&mut self) {
+        if self.is_max_level() { return; }
+
+        self.stats.level += 1;
+        self.stats.damage *= LEVEL_DAMAGE_MULT;
+        self.stats.cooldown *= LEVEL_COOLDOWN_MULT;
+        self.stats.area *= LEVEL_AREA_MULT;
+
+        self.behaviour.level_up(&mut self.stats);
+    }
+}
+
+/// The weapons an `Entity` (the player) is carrying, up to `max_slots` different kinds.
+#[derive(Component, Debug)]
+pub struct WeaponSlots {
+    pub max_slots: usize,
+    weapons: Vec<Weapon>,
+}
+impl Default for WeaponSlots {
+    fn default() -> Self {
+        Self {
+            max_slots: MAX_WEAPON_SLOTS,
+            weapons: vec![Weapon::new(WeaponKind::Dagger)],
+        }
+    }
+}
+impl WeaponSlots {
+    pub fn get(&self, kind: WeaponKind) -> Option<&Weapon> {
+        self.weapons.iter().find(|w| w.kind == kind)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.weapons.len() >= self.max_slots
+    }
+
+    /// Levels up `kind` if it's already carried, otherwise adds it (if there is a free slot).
+    /// Returns false if nothing changed.
+    pub fn add_or_level_up(&mut self, kind: WeaponKind) -> bool {
+        if let Some(weapon) = self.weapons.iter_mut().find(|w| w.kind == kind) {
+            if weapon.is_max_level() { return false; }
+            weapon.level_up();
+            return true;
+        }
+
+        if self.is_full() { return false; }
+        self.weapons.push(Weapon::new(kind));
+        true
+    }
+}
+
+/// Short lived visual for weapons that don't spawn anything (ie. an aura pulse or a sword swing).
+#[derive(Component, Debug)]
+pub struct WeaponEffect(pub Timer);
+
+/// Meshes and materials for the weapon effects, generated at startup.
+#[derive(Resource, Default)]
+pub struct WeaponAssets {
+    pub pulse_mesh: Handle<Mesh>,
+    pub pulse_material: Handle<StandardMaterial>,
+    pub slash_mesh: Handle<Mesh>,
+    pub slash_material: Handle<StandardMaterial>,
+}
+
+
+pub struct WeaponPlugin;
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Resources
+            .insert_resource(WeaponAssets::default())
+
+            // Systems
+            .add_systems(Startup, create_weapon_assets)
+            .add_systems(FixedUpdate,
+                fire_weapons
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::Spawn)
+            )
+            .add_systems(FixedUpdate,
+                orbit_owners
+                .after(push_out_of_obstacles)
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::EntityUpdates)
+            )
+            .add_systems(FixedUpdate,
+                expire_weapon_effects
+                .run_if(in_state(GameState::Playing))
+                .in_set(GameLoopSchedules::Despawn)
+            )
+        ;
+    }
+}
+
+fn create_weapon_assets(
+    mut assets: ResMut<WeaponAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let effect_material = |color: Color| StandardMaterial {
+        base_color: color,
+        emissive: color * 0.5,
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    };
+
+    // unit sized, effects scale them up to their weapon's `area`
+    assets.pulse_mesh = meshes.add(Circle::new(1.0));
+    assets.pulse_material = materials.add(effect_material(Color::rgba(1.0, 0.85, 0.3, 0.25)));
+    assets.slash_mesh = meshes.add(Rectangle::new(1.0, 1.0));
+    assets.slash_material = materials.add(effect_material(Color::rgba(0.9, 0.9, 1.0, 0.5)));
+}
+
+fn fire_weapons(
+    time: Res<Time>,
+    index: Res<SpatialIndex>,
+    assets: Res<WeaponAssets>,
+    mut q_player: Query<(Entity, &Transform, &CharacterStats, &mut WeaponSlots), (With<PlayerComponent>, Without<Dying>)>,
+    mut output: WeaponOutput,
+) {
+    let (owner, transform, owner_stats, mut slots) = if let Ok(res) = q_player.get_single_mut() {
+        res
+    } else { return; };
+
+    let mut ctx = FireContext {
+        owner,
+        origin: transform.translation,
+        facing: (transform.rotation * Vec3::Y).truncate().normalize_or_zero(),
+        index: &index,
+        assets: &assets,
+        commands: &mut output.commands,
+        meshes: &mut output.meshes,
+        damage: &mut output.damage,
+        statuses: &mut output.statuses,
+    };
+
+    for weapon in slots.weapons.iter_mut() {
+        let stats = weapon.stats.with_owner_stats(owner_stats);
+        // the owner's cooldown stat can change at any time, so keep the timer in line with it
+        let cooldown = std::time::Duration::from_secs_f32(stats.cooldown.max(0.05));
+        if weapon.cooldown.duration() != cooldown {
+            weapon.cooldown.set_duration(cooldown);
+        }
+
+        if !weapon.cooldown.tick(time.delta()).just_finished() { continue; }
+        weapon.behaviour.fire(&stats, &mut ctx);
+    }
+}
+
+fn orbit_owners(
+    mut commands: Commands,
+    time: Res<Time>,
+    q_owners: Query<&Transform, Without<Orbit>>,
+    mut q_orbiting: Query<(Entity, &mut Orbit, &mut Transform)>,
+) {
+    for (entity, mut orbit, mut transform) in q_orbiting.iter_mut() {
+        let center = if let Ok(t) = q_owners.get(orbit.owner) {
+            t.translation
+        } else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+
+        orbit.angle += orbit.speed * time.delta_seconds();
+        let offset = Vec2::from_angle(orbit.angle) * orbit.radius;
+        transform.translation = center + offset.extend(0.0);
+        // local Y points along the direction of travel (tangent to the orbit), same as `update_facing`
+        transform.rotation = Quat::from_rotation_z(orbit.angle);
+    }
+}
+
+fn expire_weapon_effects(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_effects: Query<(Entity, &mut WeaponEffect)>,
+) {
+    for (entity, mut effect) in q_effects.iter_mut() {
+        if effect.0.tick(time.delta()).just_finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -1,3 +1,9 @@
+// bevy system params (queries with filters, lots of resources) trip these constantly
+#![allow(clippy::type_complexity)]
+#![allow(clippy::too_many_arguments)]
+
+use std::process::ExitCode;
+
 use bevy::prelude::*;
 // use bevy::log::LogPlugin;
 // use bevy_inspector_egui::quick::WorldInspectorPlugin;
@@ -5,29 +11,24 @@ use bevy::prelude::*;
 mod horde_survivors;
 use horde_survivors::prelude::*;
 
-fn main() {
+fn main() -> ExitCode {
+    let replay_mode = ReplayMode::from_args();
+
     let mut app = App::new();
-    app.add_plugins(DefaultPlugins)
+    if replay_mode.is_headless() {
+        app.add_plugins(headless_plugins());
+    } else {
+        app.add_plugins(DefaultPlugins);
+    }
     // app.add_plugins(DefaultPlugins.build().disable::<LogPlugin>())
     //     .add_plugins(WorldInspectorPlugin::new())
-        .add_plugins((
-            StatePlugin,
-            SchedulesPlugin,
-
-            LightingPlugin,
-            CameraPlugin,
-
-            AssetHandlerPlugin,
-            
-            MovementPlugin,
-            
-            PlayerPlugin,
-            EnemyPlugin,
-        ))
+    app.add_plugins(GamePlugins(replay_mode))
         .add_systems(Startup, setup_test_scene);
     
     // bevy_mod_debugdump::print_schedule_graph(&mut app, Update);
     app.run();
+
+    if replay_failed() { ExitCode::FAILURE } else { ExitCode::SUCCESS }
 }
 
 